@@ -1,26 +1,123 @@
-use std::fs::File;
-use serde::Serialize;
-use clap::Parser;
-use dsa_tools_rust::*;
+use clap::{Parser, Subcommand};
+use dsa_tools_rust::Format;
+use dsa_tools_rust::envelope::{Envelope, Provenance};
+use dsa_tools_rust::error::Error;
+use dsa_tools_rust::output::Output;
+use dsa_tools_rust::poison::{Catalogue, LevelRange, Listing, Poison, PoisonKind, SymptomTable};
+use dsa_tools_rust::resistance::{resist, Effect, Resistance};
+use dsa_tools_rust::simulation::{simulate_with, Victim};
 use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+const TOOL: &str = "dsa-gift";
 
 #[derive(Parser)]
 struct Cli {
-    #[arg(short = 'v', long = "verbose", default_value_t = false, 
+    #[arg(short = 'v', long = "verbose", global = true, default_value_t = false, 
         help = "Spuckt unnötig viel Holz aus")]
     verbose: bool,
-    #[arg(short = 'o', long = "output", default_value = None,
+    #[arg(short = 'o', long = "output", global = true, default_value = None,
         help = "Der Speicherort für die Ausgabe. Standard ist stdout.")]
     outfile: Option<std::path::PathBuf>,
-    #[arg(short = 'f', long = "format", default_value_t = Format::TEXT, ignore_case = true, 
+    #[arg(short = 'a', long = "append", global = true, default_value_t = false,
+        help = "Hängt die Ausgabe an die Datei aus -o an, statt sie zu überschreiben.")]
+    append: bool,
+    #[arg(short = 'f', long = "format", global = true, default_value_t = Format::TEXT, ignore_case = true, 
         help = "Ausgabeformat: Freitext, md, json oder csv. Standard ist Freitext.", hide_possible_values = true, hide_default_value = true)]
     format: Format,
-    #[arg(short = 'x', long = "seed", default_value = None,
+    #[arg(long = "delimiter", global = true, default_value_t = ',',
+        help = "Das Trennzeichen für -f csv, z.B. ';' für ein deutsches Excel. Standard ist ','.", hide_default_value = true)]
+    delimiter: char,
+    #[arg(short = 'x', long = "seed", global = true, default_value = None,
         help = "Setze den Seed manuell.", hide_default_value = true)]
     seed: Option<i64>,
-    #[arg(short = 'l', long = "level", default_value_t = 1,
+    #[arg(short = 'l', long = "level", global = true, default_value_t = 1,
         help = "Die Giftstufe.", hide_default_value = true)]
     level: u32,
+    #[arg(short = 'k', long = "kind", global = true, default_value = "einnahme", ignore_case = true,
+        help = "Die Art des Gifts: einnahme, waffen, atem oder kontakt. Standard ist einnahme.", hide_possible_values = true, hide_default_value = true)]
+    kind: PoisonKind,
+    #[arg(long = "symptoms", global = true, default_value = None,
+        help = "Eine JSON Datei mit einer eigenen Symptomtabelle. Standard ist die Tabelle aus dem ZBA.")]
+    symptoms: Option<std::path::PathBuf>,
+    #[arg(short = 'p', long = "poison", global = true, default_value = None,
+        help = "Liest das Gift aus einer mit -f json gespeicherten Datei, statt ein neues zu erzeugen.")]
+    poison: Option<std::path::PathBuf>,
+    #[arg(long = "name", global = true, default_value = None,
+        help = "Nimmt das Gift mit diesem Namen aus der Sammlung, z.B. Kukris.")]
+    name: Option<String>,
+    #[arg(long = "list", default_value = None, num_args = 0..=1, value_name = "STUFE",
+        help = "Listet die Gifte der Sammlung auf, optional nur die einer Stufe.")]
+    list: Option<Option<u32>>,
+    #[arg(long = "catalogue", global = true, default_value = None,
+        help = "Eine JSON Datei mit eigenen Giften, die die eingebaute Sammlung ergänzen.")]
+    catalogue: Option<std::path::PathBuf>,
+    #[arg(short = 'n', long = "count", default_value_t = 1, conflicts_with_all = ["poison", "name"],
+        help = "Die Anzahl der Gifte, die erzeugt werden.", hide_default_value = true)]
+    count: u32,
+    #[arg(long = "level-range", default_value = None, conflicts_with_all = ["poison", "name"],
+        help = "Würfelt die Stufe jedes Gifts aus dem Bereich, z.B. 3..8. Ersetzt -l.")]
+    level_range: Option<LevelRange>,
+    #[arg(long = "replay", global = true, default_value = None,
+        help = "Erzeugt eine mit -f json gespeicherte Ausgabe mit Seed und Eingaben aus der Datei erneut. Format und Ausgabe kommen weiter von -f und -o.")]
+    replay: Option<std::path::PathBuf>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Clone, Serialize, Deserialize)]
+enum Command {
+    /// Spielt die Wirkung des Gifts an einem Opfer durch
+    Simulate {
+        #[arg(long = "lep", help = "Die Lebenspunkte des Opfers.")]
+        lep: i32,
+        #[arg(long = "probe", default_value_t = false,
+            help = "Würfelt vorher die KO-Probe gegen das Gift und spielt nur die verbleibende Wirkung durch.")]
+        check: bool,
+        #[command(flatten)]
+        resistance: Resistance,
+    },
+    /// Würfelt die KO-Probe gegen das Gift und zeigt, welche Symptome auftreten
+    Resist {
+        #[command(flatten)]
+        resistance: Resistance,
+    },
+}
+/// Die Eingaben, wie sie im Umschlag der JSON Ausgabe stehen
+#[derive(Serialize, Deserialize)]
+struct Params {
+    level: u32,
+    kind: PoisonKind,
+    count: u32,
+    level_range: Option<LevelRange>,
+    name: Option<String>,
+    poison: Option<std::path::PathBuf>,
+    symptoms: Option<std::path::PathBuf>,
+    catalogue: Option<std::path::PathBuf>,
+    list: bool,
+    list_level: Option<u32>,
+    command: Option<Command>
+}
+impl Cli {
+    fn params(&self) -> Params {
+        Params { level: self.level, kind: self.kind, count: self.count, level_range: self.level_range, name: self.name.clone(), poison: self.poison.clone(),
+            symptoms: self.symptoms.clone(), catalogue: self.catalogue.clone(),
+            list: self.list.is_some(), list_level: self.list.flatten(), command: self.command.clone() }
+    }
+    /// Übernimmt Seed und Eingaben aus einer früheren Ausgabe
+    fn replay(&mut self, seed: u64, params: Params) {
+        self.seed = Some(seed as i64);
+        self.level = params.level;
+        self.kind = params.kind;
+        self.count = params.count;
+        self.level_range = params.level_range;
+        self.name = params.name;
+        self.poison = params.poison;
+        self.symptoms = params.symptoms;
+        self.catalogue = params.catalogue;
+        self.list = if params.list {Some(params.list_level)} else {None};
+        self.command = params.command;
+    }
 }
 impl std::fmt::Display for Cli {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -29,174 +126,99 @@ impl std::fmt::Display for Cli {
             Some(f) => f,
             None => &binding,
         };
-        write!(f, "Level: {}, Output: {}, Format: {}", self.level, file.display(), self.format)
+        write!(f, "Level: {}, Art: {}, Output: {}, Format: {}", self.level, self.kind, file.display(), self.format)
     }
 }
 
 fn log(args: &Cli, msg: &impl std::fmt::Display) {
     if args.verbose {
-        println!("{}", msg)
-    }
-}
-
-#[derive(Clone, Default, Serialize)]
-struct Symptom {
-    amount: u32,
-    name: Box<str>,
-    characteristic: Option<Characteristic>,
-    disadvantage: Option<String>,
-    unconsciousness: bool
-}
-impl std::fmt::Display for Symptom {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.name)?;
-        match &self.characteristic {
-            Some(c) => write!(f, " ({} -{}W6)", c, self.amount)?,
-            None => (),
-        };
-        match &self.disadvantage {
-            Some(d) => write!(f, " ({} +{}W6)", d, self.amount)?,
-            None => (),
-        };
-        if self.unconsciousness {write!(f, " (bewusstlos)")?;}
-        Ok(())
+        eprintln!("{}", msg)
     }
 }
-fn roll_symptom (rng: &mut rand::rngs::StdRng) -> Symptom {
-    let roll:u32 = rng.gen_range(1..=20);
 
-    match roll {
-        1..=4 => Symptom{name: "Erbrechen".into(), characteristic: Some(Characteristic::CH), ..Default::default()},
-        5..=6 => Symptom{name: "Durchfall / Koliken".into(), characteristic: Some(Characteristic::MU), ..Default::default()},
-        7..=8 => Symptom{name: "Schweißausbrüche / Atemnot".into(), characteristic: Some(Characteristic::KO), ..Default::default()},
-        9..=10 => Symptom{name: "Schwäche".into(), characteristic: Some(Characteristic::KK), ..Default::default()},
-        11..=12 => Symptom{name: "Kopfschmerz / Schwindel".into(), characteristic: Some(Characteristic::KL), ..Default::default()},
-        13..=14 => Symptom{name: "Lähmungen".into(), characteristic: Some(Characteristic::GE), ..Default::default()},
-        15..=16 => Symptom{name: "Taubheiten".into(), characteristic: Some(Characteristic::FF), ..Default::default()},
-        17 => Symptom{name: "Schwellungen".into(), characteristic: Some(Characteristic::GE), ..Default::default()},
-        18 => Symptom{name: "Erregung".into(), disadvantage: Some("Jähzorn".to_string()), ..Default::default()},
-        19 => Symptom{name: "Blutungen".into(), disadvantage: Some("Aberglaube".to_string()), ..Default::default()},
-        _ => Symptom { name: "Bewusstlosigkeit".into(), unconsciousness: true, ..Default::default()}
+/// Zufallsgenerator für Gift beliebiger Stufe
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("{}", e);
+        std::process::exit(e.exit_code());
     }
 }
 
-#[derive(Serialize)]
-struct SymptomList {
-    symptoms: Vec<Symptom>
-}
-impl SymptomList {
-    fn push(&mut self, x: &mut Symptom) {
-        for s in &mut self.symptoms {
-            if s.name == x.name {s.amount += 1; return;}
+fn run(mut args: Cli) -> Result<(), Error> {
+    if let Some(path) = args.replay.clone() {
+        let envelope: Envelope<serde_json::Value, serde_json::Value> = Envelope::load(&path)?;
+        if envelope.tool != TOOL {
+            return Err(Error::WrongTool(path, envelope.tool));
         }
-        x.amount = 1;
-        self.symptoms.push(x.clone());
-    }
-}
-impl std::fmt::Display for SymptomList {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[")?;
-        for s in 0..self.symptoms.len() {
-            write!(f, "{}", self.symptoms[s])?;
-            if s < (self.symptoms.len()-1) {write!(f, ", ")?;}
+        if let Some(warning) = envelope.version_warning() {
+            eprintln!("{}", warning);
         }
-        write!(f, "]")
-    }
-}
-
-#[derive(Serialize)]
-struct Poison {
-    level: u32,
-    start: DiceOverTime,
-    damage: DiceOverTime,
-    duration: DiceOverTime,
-    symptoms: SymptomList
-}
-impl Poison {
-    fn md(&self) -> String {
-        format!("- Stufe {}\n- Beginn nach {}\n- Dauer {}\n- Schaden {} pro {}\n- {}", self.level, self.start, self.duration, self.damage.roll_only(), self.damage.time, self.symptoms)
+        let params = serde_json::from_value(envelope.params).map_err(|e| Error::Json(path.clone(), e))?;
+        args.replay(envelope.seed, params);
     }
-    fn csv(&self) -> String{
-        format!("\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"", self.level, self.start, self.damage, self.duration, self.symptoms)
-    }
-    fn json(&self) -> String {
-        match serde_json::to_string(self) {
-            Ok(json) => json,
-            Err(e) => panic!("{}", e)
-        }
-    }
-}
-impl std::fmt::Display for Poison {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Stufe {}\nBeginn nach {}\nDauer {}\nSchaden {} pro {}\n{}", self.level, self.start, self.duration, self.damage.roll_only(), self.damage.time, self.symptoms)
-    }
-}
-
-/// Zufallsgenerator für Gift beliebiger Stufe
-fn main() {
-    let args = Cli::parse();
     let s = match args.seed {
         Some(s) => s as u64,
         None => rand::thread_rng().gen(),
     };
-    let mut rng = rand::rngs::StdRng::seed_from_u64(s as u64);
+    let mut rng = rand::rngs::StdRng::seed_from_u64(s);
     log(&args, &args);
 
-    if args.level < 1 || args.level > 20 {
-        panic!("Stufe war: {}\nMuss zwischen 1 und 20 liegen.", args.level);
+    let table = match &args.symptoms {
+        Some(path) => SymptomTable::load(path)?,
+        None => SymptomTable::default(),
+    };
+    let mut catalogue = Catalogue::builtin();
+    if let Some(path) = &args.catalogue {
+        catalogue.merge(Catalogue::load(path)?);
     }
 
-    let dice = (args.level as f64/ 4.0).ceil() as u32;
-    log(&args, &format!("Schadenswürfel: {}", dice));
-
-    let start: DiceOverTime;
-    let damage: DiceOverTime;
-    let duration: DiceOverTime;
-    match args.level {
-        1..=5 => {
-            start = DiceOverTime{dice: 1, ..Default::default()};
-            damage = DiceOverTime{dice: 1, time: Timeunit::STD, ..Default::default()};
-            duration = DiceOverTime{flat: dice, ..Default::default()};
-        },
-        6..=9 => {
-            start = DiceOverTime{flat: 1, ..Default::default()};
-            damage = DiceOverTime{dice: 1, ..Default::default()};
-            duration = DiceOverTime{flat: dice, ..Default::default()};
-        },
-        10..=15 => {
-            start = DiceOverTime{dice: 1, flat: 4, time: Timeunit::KR};
-            damage = DiceOverTime{dice: 2, ..Default::default()};
-            duration = DiceOverTime{flat: dice/2, ..Default::default()};
-        },
-        16..=20 => {
-            start = DiceOverTime{dice: 1, time: Timeunit::KR, ..Default::default()};
-            damage = DiceOverTime{dice: 1, time: Timeunit::KR, ..Default::default()};
-            duration = DiceOverTime{flat: dice, time: Timeunit::KR, ..Default::default()};
-        },
-        _ => panic!()
+    let output = Output::new(args.format.clone(), Provenance::new(TOOL, s))
+        .with_file(args.outfile.clone(), args.append)
+        .with_delimiter(args.delimiter);
+    if let Some(level) = args.list {
+        let poisons: Vec<Listing> = match level {
+            Some(l) => catalogue.by_level(l).into_iter().map(|p| Listing(p.clone())).collect(),
+            None => catalogue.poisons.iter().map(|p| Listing(p.clone())).collect(),
+        };
+        return Ok(output.write(&args.params(), &poisons)?);
     }
-    
-    log(&args, &start);
-    log(&args, &damage);
-    log(&args, &duration);
 
-    let mut symptoms:SymptomList = SymptomList { symptoms: Vec::new() };
-    for _ in 0..(args.level as f64/ 2.0).ceil() as u32 {
-        symptoms.push(&mut roll_symptom(&mut rng))
+    if args.count == 0 {
+        return Err(Error::Usage("-n muss mindestens 1 sein".to_string()));
+    }
+    if args.count > 1 || args.level_range.is_some() {
+        if args.command.is_some() {
+            return Err(Error::Usage("simulate und resist gehen nur mit einem einzelnen Gift, nicht mit -n oder --level-range".to_string()));
+        }
+        let levels = args.level_range.unwrap_or(LevelRange::single(args.level));
+        let poisons = Poison::generate_batch(args.count, &levels, args.kind, &table, &mut rng)?;
+        return Ok(output.write(&args.params(), &poisons)?);
     }
 
-    let p = Poison{level: args.level, start: start, damage: damage, duration: duration, symptoms: symptoms };
-    let mut file: Box<dyn std::io::Write> = match args.outfile {
-        Some(f) => match File::create(f) {
-            Ok(fi) => Box::new(fi),
-            Err(_) => Box::new(std::io::stdout()),
-        },
-        None => Box::new(std::io::stdout()),
-    };
-    let _ = match args.format {
-        Format::TEXT => write!(file, "{}", p),
-        Format::CSV => write!(file, "{}", p.csv()),
-        Format::MD => write!(file, "{}", p.md()),
-        Format::JSON => write!(file, "{}", p.json())
+    let p = match (&args.poison, &args.name) {
+        (Some(path), _) => Poison::load(path)?,
+        (None, Some(name)) => catalogue.find(name)?.clone(),
+        (None, None) => Poison::generate_with(args.level, &table, &mut rng)?.with_kind(args.kind),
     };
+    log(&args, &p.start);
+    log(&args, &p.damage);
+    log(&args, &p.duration);
+
+    match &args.command {
+        Some(Command::Resist { resistance }) => {
+            let check = resist(&p, resistance, &mut rng);
+            output.write(&args.params(), &[check])?;
+        },
+        Some(Command::Simulate { lep, check, resistance }) => {
+            let effect = if *check {
+                let check = resist(&p, resistance, &mut rng);
+                log(&args, &check);
+                check.effect
+            } else {Effect::FULL};
+            let events = simulate_with(&p, &Victim { lep: *lep, ko: resistance.ko }, effect, &mut rng)?;
+            output.write(&args.params(), &events)?;
+        },
+        None => output.write(&args.params(), &[p])?,
+    }
+    Ok(())
 }
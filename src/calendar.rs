@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+use crate::weather::Season;
+
+#[derive(Debug, PartialEq)]
+pub enum DateError {
+    Invalid(String)
+}
+impl std::fmt::Display for DateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateError::Invalid(s) => write!(f, "Ungültiges Datum: \"{}\". Erwartet wird z.B. \"1. Praios 1040 BF\"", s),
+        }
+    }
+}
+impl std::error::Error for DateError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Month {
+    PRAIOS, RONDRA, EFFERD, TRAVIA, BORON, HESINDE, FIRUN, TSA, PHEX, PERAINE, INGERIMM, RAHJA, NAMELESS
+}
+impl Month {
+    const ALL: [Month; 13] = [Month::PRAIOS, Month::RONDRA, Month::EFFERD, Month::TRAVIA, Month::BORON, Month::HESINDE,
+        Month::FIRUN, Month::TSA, Month::PHEX, Month::PERAINE, Month::INGERIMM, Month::RAHJA, Month::NAMELESS];
+
+    pub fn days(&self) -> u32 {
+        match self {
+            Month::NAMELESS => 5,
+            _ => 30,
+        }
+    }
+    pub fn season(&self) -> Season {
+        match self {
+            Month::EFFERD | Month::TRAVIA | Month::BORON => Season::AUTUMN,
+            Month::HESINDE | Month::FIRUN | Month::TSA => Season::WINTER,
+            Month::PHEX | Month::PERAINE | Month::INGERIMM => Season::SPRING,
+            Month::RAHJA | Month::NAMELESS | Month::PRAIOS | Month::RONDRA => Season::SUMMER,
+        }
+    }
+    fn index(&self) -> u32 {
+        Month::ALL.iter().position(|m| m == self).unwrap_or_default() as u32
+    }
+}
+impl std::str::FromStr for Month {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        match name.as_str() {
+            "namenloser tag" | "namenlose tage" | "namenlosen tage" | "nt" => return Ok(Month::NAMELESS),
+            _ => (),
+        }
+        Month::ALL.iter()
+            .find(|m| m.to_string().to_lowercase() == name)
+            .copied()
+            .ok_or(DateError::Invalid(s.to_string()))
+    }
+}
+impl std::fmt::Display for Month {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Month::PRAIOS => write!(f, "Praios"),
+            Month::RONDRA => write!(f, "Rondra"),
+            Month::EFFERD => write!(f, "Efferd"),
+            Month::TRAVIA => write!(f, "Travia"),
+            Month::BORON => write!(f, "Boron"),
+            Month::HESINDE => write!(f, "Hesinde"),
+            Month::FIRUN => write!(f, "Firun"),
+            Month::TSA => write!(f, "Tsa"),
+            Month::PHEX => write!(f, "Phex"),
+            Month::PERAINE => write!(f, "Peraine"),
+            Month::INGERIMM => write!(f, "Ingerimm"),
+            Month::RAHJA => write!(f, "Rahja"),
+            Month::NAMELESS => write!(f, "Namenloser Tag"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Weekday {
+    WINDSTAG, ERDSTAG, MARKTTAG, PRAIOSTAG, ROHALSTAG, FEUERTAG, WASSERTAG
+}
+impl std::fmt::Display for Weekday {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Weekday::WINDSTAG => write!(f, "Windstag"),
+            Weekday::ERDSTAG => write!(f, "Erdstag"),
+            Weekday::MARKTTAG => write!(f, "Markttag"),
+            Weekday::PRAIOSTAG => write!(f, "Praiostag"),
+            Weekday::ROHALSTAG => write!(f, "Rohalstag"),
+            Weekday::FEUERTAG => write!(f, "Feuertag"),
+            Weekday::WASSERTAG => write!(f, "Wassertag"),
+        }
+    }
+}
+
+/// Ein Datum nach Bosparans Fall: zwölf Monate zu 30 Tagen, danach die fünf Namenlosen Tage.
+/// Aus JSON gelesene Daten werden wie bei `AvDate::new` geprüft.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawDate")]
+pub struct AvDate {
+    pub day: u32,
+    pub month: Month,
+    pub year: i32
+}
+/// Ein Datum, wie es im JSON steht, noch ungeprüft
+#[derive(Deserialize)]
+struct RawDate {
+    day: u32,
+    month: Month,
+    year: i32
+}
+impl TryFrom<RawDate> for AvDate {
+    type Error = DateError;
+
+    fn try_from(date: RawDate) -> Result<Self, Self::Error> {
+        AvDate::new(date.day, date.month, date.year)
+    }
+}
+impl AvDate {
+    pub fn new(day: u32, month: Month, year: i32) -> Result<Self, DateError> {
+        if day < 1 || day > month.days() {
+            return Err(DateError::Invalid(format!("{}. {} {} BF", day, month, year)));
+        }
+        Ok(AvDate { day, month, year })
+    }
+    pub fn next(&self) -> AvDate {
+        if self.day < self.month.days() {
+            return AvDate { day: self.day + 1, ..*self };
+        }
+        match self.month {
+            Month::NAMELESS => AvDate { day: 1, month: Month::PRAIOS, year: self.year + 1 },
+            m => AvDate { day: 1, month: Month::ALL[m.index() as usize + 1], year: self.year },
+        }
+    }
+    /// Der Tag im Jahr, beginnend bei 0 für den 1. Praios
+    pub fn day_of_year(&self) -> u32 {
+        self.month.index() * 30 + self.day - 1
+    }
+    /// Gezählt wird ab dem 1. Praios 1000 BF als Praiostag. Da das Jahr 365 Tage hat, verschiebt sich der Wochentag jedes Jahr um einen.
+    pub fn weekday(&self) -> Weekday {
+        let days = (self.year as i64 - 1000) * 365 + self.day_of_year() as i64;
+        const WEEK: [Weekday; 7] = [Weekday::PRAIOSTAG, Weekday::ROHALSTAG, Weekday::FEUERTAG, Weekday::WASSERTAG,
+            Weekday::WINDSTAG, Weekday::ERDSTAG, Weekday::MARKTTAG];
+        WEEK[days.rem_euclid(7) as usize]
+    }
+    pub fn season(&self) -> Season {
+        self.month.season()
+    }
+}
+impl std::str::FromStr for AvDate {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DateError::Invalid(s.to_string());
+        let mut words: Vec<&str> = s.split_whitespace().collect();
+        if words.last().is_some_and(|w| w.eq_ignore_ascii_case("BF")) {
+            words.pop();
+        }
+        if words.len() < 3 {
+            return Err(invalid());
+        }
+        let day: u32 = words[0].trim_end_matches('.').parse().map_err(|_| invalid())?;
+        let year: i32 = words[words.len()-1].parse().map_err(|_| invalid())?;
+        let month: Month = words[1..words.len()-1].join(" ").parse().map_err(|_| invalid())?;
+        AvDate::new(day, month, year).map_err(|_| invalid())
+    }
+}
+impl std::fmt::Display for AvDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}. {} {} BF", self.day, self.month, self.year)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32, month: Month, year: i32) -> AvDate {
+        AvDate::new(day, month, year).unwrap()
+    }
+
+    #[test]
+    fn next_runs_through_the_nameless_days_into_the_new_year() {
+        let mut d = date(30, Month::RAHJA, 1040);
+        d = d.next();
+        assert_eq!(d, date(1, Month::NAMELESS, 1040));
+        for _ in 0..4 {d = d.next();}
+        assert_eq!(d, date(5, Month::NAMELESS, 1040));
+        assert_eq!(d.next(), date(1, Month::PRAIOS, 1041));
+    }
+
+    #[test]
+    fn weekday_moves_by_one_each_year() {
+        assert_eq!(date(1, Month::PRAIOS, 1000).weekday(), Weekday::PRAIOSTAG);
+        assert_eq!(date(2, Month::PRAIOS, 1000).weekday(), Weekday::ROHALSTAG);
+        assert_eq!(date(8, Month::PRAIOS, 1000).weekday(), Weekday::PRAIOSTAG);
+        assert_eq!(date(1, Month::PRAIOS, 1001).weekday(), Weekday::ROHALSTAG);
+        assert_eq!(date(1, Month::PRAIOS, 999).weekday(), Weekday::MARKTTAG);
+    }
+
+    #[test]
+    fn parses_dates_with_and_without_bf() {
+        assert_eq!("1. Praios 1040 BF".parse(), Ok(date(1, Month::PRAIOS, 1040)));
+        assert_eq!("12. rahja 1039".parse(), Ok(date(12, Month::RAHJA, 1039)));
+        assert_eq!("3. Namenlose Tage 1040 BF".parse(), Ok(date(3, Month::NAMELESS, 1040)));
+    }
+
+    #[test]
+    fn rejects_days_outside_the_month() {
+        assert!("0. Praios 1040 BF".parse::<AvDate>().is_err());
+        assert!("31. Praios 1040 BF".parse::<AvDate>().is_err());
+        assert!("6. Namenlose Tage 1040 BF".parse::<AvDate>().is_err());
+        assert!("1. Praiosmond 1040 BF".parse::<AvDate>().is_err());
+    }
+
+    #[test]
+    fn deserialize_checks_the_day() {
+        assert!(serde_json::from_str::<AvDate>(r#"{"day": 0, "month": "PRAIOS", "year": 1040}"#).is_err());
+        assert!(serde_json::from_str::<AvDate>(r#"{"day": 6, "month": "NAMELESS", "year": 1040}"#).is_err());
+        assert_eq!(serde_json::from_str::<AvDate>(r#"{"day": 5, "month": "NAMELESS", "year": 1040}"#).unwrap(), date(5, Month::NAMELESS, 1040));
+    }
+}
@@ -0,0 +1,90 @@
+use std::io::Write;
+
+/// Ein Datensatz, der sich als CSV Zeile schreiben lässt
+pub trait CsvRecord: Sized {
+    /// Die Kopfzeile. Sie bekommt alle Datensätze, damit sich Spalten wie "Symptom 1".."Symptom n" nach dem längsten richten können.
+    fn header(records: &[Self]) -> Vec<String>;
+    fn fields(&self) -> Vec<String>;
+}
+
+/// Nummerierte Spalten wie "Symptom 1", "Symptom 2" für den Datensatz mit den meisten Einträgen
+pub fn numbered(name: &str, count: usize) -> Vec<String> {
+    (1..=count).map(|i| format!("{} {}", name, i)).collect()
+}
+
+/// Setzt ein Feld nach RFC 4180 in Anführungszeichen, wenn es das Trennzeichen, Anführungszeichen oder Zeilenumbrüche enthält
+pub fn escape(field: &str, delimiter: char) -> String {
+    if field.contains(delimiter) || field.contains('"') || field.contains('\n') || field.contains('\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Zerlegt eine Zeile nach RFC 4180, etwa die Kopfzeile einer bestehenden Datei
+pub fn parse_row(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches(['\r', '\n']).chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {field.push('"'); chars.next();},
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Schreibt CSV nach RFC 4180 mit Kopfzeile. Das Trennzeichen ist wählbar, z.B. ';' für ein deutsches Excel.
+pub struct CsvWriter<W: Write> {
+    out: W,
+    delimiter: char,
+    columns: Vec<(String, String)>
+}
+impl<W: Write> CsvWriter<W> {
+    pub fn new(out: W, delimiter: char) -> Self {
+        CsvWriter { out, delimiter, columns: Vec::new() }
+    }
+    /// Spalten mit festem Wert, die an jede Zeile angehängt werden, z.B. der Seed
+    pub fn with_columns(mut self, columns: Vec<(String, String)>) -> Self {
+        self.columns = columns;
+        self
+    }
+    pub fn write_row(&mut self, row: &[String]) -> std::io::Result<()> {
+        let fields: Vec<String> = row.iter().map(|f| escape(f, self.delimiter)).collect();
+        write!(self.out, "{}\r\n", fields.join(&self.delimiter.to_string()))
+    }
+    /// Schreibt die Kopfzeile und danach alle Datensätze
+    pub fn write_records<T: CsvRecord>(&mut self, records: &[T]) -> std::io::Result<()> {
+        let mut header = T::header(records);
+        let width = header.len();
+        header.extend(self.columns.iter().map(|(name, _)| name.clone()));
+        self.write_row(&header)?;
+        self.write_rows(records, width)
+    }
+    /// Wie viele Datenspalten eine bestehende Kopfzeile für diese Datensätze hat. Sie muss mit den eigenen Spalten beginnen
+    /// und mit den festen Spalten enden, dazwischen sind weitere Spalten wie "Symptom 7" erlaubt. Sonst `None`.
+    pub fn fits<T: CsvRecord>(&self, records: &[T], existing: &[String]) -> Option<usize> {
+        let own = T::header(records);
+        let fixed: Vec<&String> = self.columns.iter().map(|(name, _)| name).collect();
+        let width = existing.len().checked_sub(fixed.len())?;
+        let matches = existing[width..].iter().eq(fixed.iter().copied()) && existing[..width].starts_with(&own);
+        if matches {Some(width)} else {None}
+    }
+    /// Schreibt nur die Datensätze, etwa beim Anhängen an eine Datei mit Kopfzeile. Kürzere Zeilen werden auf `width` Felder aufgefüllt.
+    pub fn write_rows<T: CsvRecord>(&mut self, records: &[T], width: usize) -> std::io::Result<()> {
+        for r in records {
+            let mut fields = r.fields();
+            if fields.len() < width {
+                fields.resize(width, String::new());
+            }
+            fields.extend(self.columns.iter().map(|(_, value)| value.clone()));
+            self.write_row(&fields)?;
+        }
+        self.out.flush()
+    }
+}
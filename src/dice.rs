@@ -0,0 +1,169 @@
+use rand::Rng;
+use serde::Serialize;
+
+#[derive(Debug, PartialEq)]
+pub enum DiceError {
+    Invalid(String),
+    NotW6(String),
+    TooLarge(String)
+}
+impl std::fmt::Display for DiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiceError::Invalid(s) => write!(f, "Ungültiger Würfelausdruck: \"{}\"", s),
+            DiceError::NotW6(s) => write!(f, "Nur W6 mit positivem Zuschlag erlaubt, war: \"{}\"", s),
+            DiceError::TooLarge(s) => write!(f, "Höchstens {} Würfel mit je {} Seiten und Faktor {} erlaubt, war: \"{}\"", MAX, MAX, MAX, s),
+        }
+    }
+}
+impl std::error::Error for DiceError {}
+
+/// Die größte Anzahl an Würfeln, Seiten und der größte Faktor, die ein Ausdruck haben darf
+pub const MAX: u32 = 1000;
+
+/// Ein Würfelausdruck in DSA Notation, z.B. `3W6+4`, `2W20-1`, `W6*2` oder `4`.
+/// Das Ergebnis ist die Augensumme mal `factor` plus `modifier`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Dice {
+    pub count: u32,
+    pub sides: u32,
+    pub factor: u32,
+    pub modifier: i32
+}
+impl Dice {
+    pub fn new(count: u32, sides: u32) -> Self {
+        Dice { count, sides, factor: 1, modifier: 0 }
+    }
+    /// Prüft die Grenzen aus `MAX` und dass jeder Würfel mindestens eine Seite hat
+    pub fn check(&self) -> Result<(), DiceError> {
+        if self.count > MAX || self.sides > MAX || self.factor > MAX {
+            return Err(DiceError::TooLarge(self.to_string()));
+        }
+        if self.sides == 0 {
+            return Err(DiceError::Invalid(self.to_string()));
+        }
+        Ok(())
+    }
+    pub fn roll(&self, rng: &mut impl Rng) -> Result<DiceRoll, DiceError> {
+        self.check()?;
+        let rolls: Vec<u32> = (0..self.count).map(|_| rng.gen_range(1..=self.sides)).collect();
+        let sum: i64 = rolls.iter().map(|r| i64::from(*r)).sum();
+        let total = sum.saturating_mul(i64::from(self.factor)).saturating_add(i64::from(self.modifier));
+        Ok(DiceRoll { total: total.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32, rolls })
+    }
+}
+impl std::str::FromStr for Dice {
+    type Err = DiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DiceError::Invalid(s.to_string());
+        let expr: String = s.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_uppercase();
+        if expr.is_empty() {
+            return Err(invalid());
+        }
+
+        let Some(w) = expr.find(['W', 'D']) else {
+            let flat: i32 = expr.parse().map_err(|_| invalid())?;
+            return Ok(Dice { count: 0, sides: 6, factor: 1, modifier: flat });
+        };
+        let count: u32 = match &expr[..w] {
+            "" => 1,
+            c => c.parse().map_err(|_| invalid())?,
+        };
+
+        let rest = &expr[w+1..];
+        let sides_end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let sides: u32 = rest[..sides_end].parse().map_err(|_| invalid())?;
+        if sides == 0 {
+            return Err(invalid());
+        }
+
+        let mut rest = &rest[sides_end..];
+        let mut factor = 1;
+        if let Some(r) = rest.strip_prefix('*') {
+            let end = r.find(|c: char| !c.is_ascii_digit()).unwrap_or(r.len());
+            factor = r[..end].parse().map_err(|_| invalid())?;
+            rest = &r[end..];
+        }
+        let modifier: i32 = match rest.chars().next() {
+            None => 0,
+            Some('+') if rest[1..].starts_with(|c: char| c.is_ascii_digit()) => rest[1..].parse().map_err(|_| invalid())?,
+            Some('-') => rest.parse().map_err(|_| invalid())?,
+            _ => return Err(invalid()),
+        };
+
+        let dice = Dice { count, sides, factor, modifier };
+        dice.check().map_err(|_| DiceError::TooLarge(s.to_string()))?;
+        Ok(dice)
+    }
+}
+impl std::fmt::Display for Dice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.count == 0 {
+            return write!(f, "{}", self.modifier);
+        }
+        write!(f, "{}W{}", self.count, self.sides)?;
+        if self.factor != 1 {write!(f, "*{}", self.factor)?;}
+        if self.modifier > 0 {write!(f, "+{}", self.modifier)?;}
+        if self.modifier < 0 {write!(f, "{}", self.modifier)?;}
+        Ok(())
+    }
+}
+
+/// Die einzelnen Würfe und das Gesamtergebnis eines Würfelausdrucks
+#[derive(Clone, Debug, Serialize)]
+pub struct DiceRoll {
+    pub rolls: Vec<u32>,
+    pub total: i32
+}
+impl std::fmt::Display for DiceRoll {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rolls: Vec<String> = self.rolls.iter().map(|r| r.to_string()).collect();
+        write!(f, "{} [{}]", self.total, rolls.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn parses_count_sides_and_modifier() {
+        assert_eq!("3W6+4".parse(), Ok(Dice { count: 3, sides: 6, factor: 1, modifier: 4 }));
+        assert_eq!("2w20-1".parse(), Ok(Dice { count: 2, sides: 20, factor: 1, modifier: -1 }));
+        assert_eq!("4".parse(), Ok(Dice { count: 0, sides: 6, factor: 1, modifier: 4 }));
+    }
+
+    #[test]
+    fn parses_factor_without_count() {
+        assert_eq!("W6*2".parse(), Ok(Dice { count: 1, sides: 6, factor: 2, modifier: 0 }));
+    }
+
+    #[test]
+    fn rejects_incomplete_and_double_signs() {
+        assert_eq!("2W6+".parse::<Dice>(), Err(DiceError::Invalid("2W6+".to_string())));
+        assert_eq!("3W6+-4".parse::<Dice>(), Err(DiceError::Invalid("3W6+-4".to_string())));
+        assert_eq!("W0".parse::<Dice>(), Err(DiceError::Invalid("W0".to_string())));
+    }
+
+    #[test]
+    fn rejects_too_large_expressions() {
+        assert_eq!("1W6*4294967295".parse::<Dice>(), Err(DiceError::TooLarge("1W6*4294967295".to_string())));
+        assert_eq!("1001W6".parse::<Dice>(), Err(DiceError::TooLarge("1001W6".to_string())));
+    }
+
+    #[test]
+    fn roll_saturates_instead_of_overflowing() {
+        let dice = Dice { count: 2, sides: 6, factor: MAX, modifier: i32::MAX };
+        let roll = dice.roll(&mut rand::rngs::StdRng::seed_from_u64(1)).unwrap();
+        assert_eq!(roll.total, i32::MAX);
+    }
+
+    #[test]
+    fn roll_rejects_values_not_built_by_the_parser() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        assert_eq!(Dice::new(1, 0).roll(&mut rng).map(|r| r.total), Err(DiceError::Invalid("1W0".to_string())));
+        assert_eq!(Dice::new(4_000_000_000, 6).roll(&mut rng).map(|r| r.total), Err(DiceError::TooLarge("4000000000W6".to_string())));
+    }
+}
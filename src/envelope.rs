@@ -0,0 +1,91 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use crate::error::read_json;
+
+/// Die Version des JSON Formats. Sie steigt, sobald sich Schlüssel ändern oder wegfallen, siehe schema/dsa-tools.schema.json.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Die Version der Tools, wie sie in jeder Ausgabe steht
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Woher eine Ausgabe stammt: Tool, Version und Seed. Damit lässt sich jedes Ergebnis wieder erzeugen.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Provenance {
+    pub tool: String,
+    pub version: String,
+    pub seed: u64
+}
+impl Provenance {
+    pub fn new(tool: &str, seed: u64) -> Self {
+        Provenance { tool: tool.to_string(), version: VERSION.to_string(), seed }
+    }
+    /// Die Spalten, die in CSV an jede Zeile angehängt werden
+    pub fn columns(&self) -> Vec<(String, String)> {
+        vec![("Seed".to_string(), self.seed.to_string()), ("Version".to_string(), format!("{} {}", self.tool, self.version))]
+    }
+    pub fn md(&self) -> String {
+        format!("_{}_", self)
+    }
+}
+impl std::fmt::Display for Provenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Seed {}, {} {}", self.seed, self.tool, self.version)
+    }
+}
+
+/// Ein Ergebnis, das neben seinen Daten auch die deutschen Anzeigetexte der einzelnen Felder mitbringt
+pub trait Labels: Serialize {
+    fn labels(&self) -> Value;
+}
+
+/// Ein Ergebnis im Umschlag: die Felder des Ergebnisses und daneben `labels` mit den Anzeigetexten
+#[derive(Serialize)]
+pub struct Entry<'a, T: Serialize> {
+    #[serde(flatten)]
+    pub value: &'a T,
+    pub labels: Value
+}
+
+/// Der versionierte Umschlag um jede JSON Ausgabe: welches Tool mit welchem Seed und welchen Eingaben was erzeugt hat
+#[derive(Serialize, Deserialize)]
+pub struct Envelope<P, R> {
+    pub schema_version: u32,
+    pub tool: String,
+    #[serde(default)]
+    pub version: String,
+    pub seed: u64,
+    pub params: P,
+    pub results: Vec<R>
+}
+impl<'a, P: Serialize, T: Labels> Envelope<&'a P, Entry<'a, T>> {
+    pub fn new(origin: &Provenance, params: &'a P, results: &'a [T]) -> Self {
+        let results = results.iter().map(|r| Entry { value: r, labels: r.labels() }).collect();
+        Envelope { schema_version: SCHEMA_VERSION, tool: origin.tool.clone(), version: origin.version.clone(), seed: origin.seed, params, results }
+    }
+}
+
+impl<P: DeserializeOwned> Envelope<P, Value> {
+    /// Liest einen Umschlag, wie ihn `-f json` schreibt, etwa um ihn mit `--replay` neu zu erzeugen
+    pub fn load(path: &std::path::Path) -> crate::error::Result<Self> {
+        read_json(path)
+    }
+}
+
+impl<P, R> Envelope<P, R> {
+    /// Ein Hinweis, wenn die Datei von einer anderen Version stammt. Dann würfelt `--replay` womöglich andere Ergebnisse.
+    pub fn version_warning(&self) -> Option<String> {
+        if self.version == VERSION {
+            return None;
+        }
+        let version = if self.version.is_empty() {"einer unbekannten Version"} else {&self.version};
+        Some(format!("Warnung: Die Datei stammt von {} {}, wiederholt wird mit {}. Die Ergebnisse können abweichen.", self.tool, version, VERSION))
+    }
+}
+
+/// Holt die Ergebnisse aus einem Umschlag. Alles andere, etwa Dateien aus älteren Versionen, wird unverändert zurückgegeben.
+pub fn unwrap_results(value: Value) -> Value {
+    match value {
+        Value::Object(mut m) if m.contains_key("schema_version") && m.contains_key("results") => m.remove("results").unwrap_or_default(),
+        v => v,
+    }
+}
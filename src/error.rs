@@ -0,0 +1,102 @@
+use std::path::{Path, PathBuf};
+use serde::{de::DeserializeOwned, Serialize};
+use crate::calendar::DateError;
+use crate::dice::DiceError;
+use crate::output::OutputError;
+use crate::poison::PoisonError;
+
+/// Alle Fehler, die die Tools melden. Die Meldungen richten sich an die Spielleitung, nicht an Entwickler.
+#[derive(Debug)]
+pub enum Error {
+    Poison(PoisonError),
+    Dice(DiceError),
+    Date(DateError),
+    /// Ein Wurf, für den die Tabelle keinen Eintrag hat
+    Roll(&'static str, u32),
+    /// Ein Wert soll vom Vortag übernommen werden, es gibt aber keinen
+    NoPreviousDay,
+    UnknownRegion(String),
+    /// Eine eigene Region mit unvollständiger Tabelle
+    InvalidRegion(PathBuf, String),
+    UnknownPoison(String),
+    /// Ein Teil eines Namens, der auf mehrere Gifte passt
+    AmbiguousPoison(String, Vec<String>),
+    InvalidLeg(String),
+    /// Eine Datei aus einem anderen Tool, etwa bei `--replay`
+    WrongTool(PathBuf, String),
+    Usage(String),
+    Io(PathBuf, std::io::Error),
+    Json(PathBuf, serde_json::Error),
+    Output(OutputError)
+}
+impl Error {
+    /// Der Exit Code nach sysexits.h
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Usage(_) | Error::UnknownRegion(_) | Error::UnknownPoison(_) | Error::AmbiguousPoison(_, _) | Error::InvalidLeg(_)
+                | Error::Dice(_) | Error::Date(_) | Error::Poison(PoisonError::LevelOutOfRange(_)) | Error::Poison(PoisonError::InvalidRange(_)) => 64,
+            Error::Poison(PoisonError::InvalidTable(_)) | Error::Poison(PoisonError::TooLarge(_)) | Error::InvalidRegion(_, _) | Error::Json(_, _) | Error::WrongTool(_, _) => 65,
+            Error::Io(_, _) => 66,
+            Error::Roll(_, _) | Error::NoPreviousDay => 70,
+            Error::Output(e) => e.exit_code(),
+        }
+    }
+}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Poison(e) => write!(f, "{}", e),
+            Error::Dice(e) => write!(f, "{}", e),
+            Error::Date(e) => write!(f, "{}", e),
+            Error::Roll(table, roll) => write!(f, "Die Tabelle {} hat keinen Eintrag für den Wurf {}", table, roll),
+            Error::NoPreviousDay => write!(f, "Das Wetter soll vom Vortag übernommen werden, es gibt aber keinen Vortag"),
+            Error::UnknownRegion(r) => write!(f, "Unbekannte Region: \"{}\"", r),
+            Error::InvalidRegion(path, e) => write!(f, "{}: Ungültige Region {}", path.display(), e),
+            Error::UnknownPoison(p) => write!(f, "Kein Gift mit dem Namen \"{}\" in der Sammlung", p),
+            Error::AmbiguousPoison(p, names) => write!(f, "\"{}\" passt auf mehrere Gifte: {}", p, names.join(", ")),
+            Error::InvalidLeg(s) => write!(f, "Ungültiger Reiseabschnitt: \"{}\". Erwartet wird z.B. \"KHOM:4:wueste\"", s),
+            Error::WrongTool(path, tool) => write!(f, "{}: Die Datei stammt von {}", path.display(), tool),
+            Error::Usage(msg) => write!(f, "{}", msg),
+            Error::Io(path, e) => write!(f, "{}: Kann die Datei nicht lesen: {}", path.display(), e),
+            Error::Json(path, e) => write!(f, "{}: Ungültiges JSON: {}", path.display(), e),
+            Error::Output(e) => write!(f, "{}", e),
+        }
+    }
+}
+impl std::error::Error for Error {}
+impl From<PoisonError> for Error {
+    fn from(e: PoisonError) -> Self {
+        Error::Poison(e)
+    }
+}
+impl From<DiceError> for Error {
+    fn from(e: DiceError) -> Self {
+        Error::Dice(e)
+    }
+}
+impl From<DateError> for Error {
+    fn from(e: DateError) -> Self {
+        Error::Date(e)
+    }
+}
+impl From<OutputError> for Error {
+    fn from(e: OutputError) -> Self {
+        Error::Output(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Liest eine JSON Datei. Fehler nennen den Pfad.
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let file = std::fs::File::open(path).map_err(|e| Error::Io(path.to_path_buf(), e))?;
+    serde_json::from_reader(std::io::BufReader::new(file)).map_err(|e| Error::Json(path.to_path_buf(), e))
+}
+/// Schreibt eine JSON Datei. Fehler zählen wie bei der Ausgabe, mit Exit Code 73 oder 74.
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let file = std::fs::File::create(path).map_err(|e| OutputError::Open(path.to_path_buf(), e))?;
+    let mut out = std::io::BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut out, value).map_err(|e| OutputError::Write(e.into()))?;
+    std::io::Write::flush(&mut out).map_err(OutputError::Write)?;
+    Ok(())
+}
@@ -1,9 +1,44 @@
+pub mod calendar;
+pub mod csv;
+pub mod dice;
+pub mod envelope;
+pub mod error;
+pub mod output;
+pub mod poison;
+pub mod resistance;
+pub mod simulation;
+pub mod weather;
+
 use clap::{ValueEnum, builder::PossibleValue};
-use serde::Serialize;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use dice::{Dice, DiceError, DiceRoll};
 
-#[derive(Serialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum Timeunit {
-    KR, SR, STD
+    KR, #[default] SR, STD
+}
+impl Timeunit {
+    /// Die Länge in KR. Eine SR sind 100 KR, eine Stunde 12 SR.
+    pub fn in_kr(&self) -> u32 {
+        match self {
+            Timeunit::KR => 1,
+            Timeunit::SR => 100,
+            Timeunit::STD => 1200,
+        }
+    }
+    pub fn shorter(&self) -> Timeunit {
+        match self {
+            Timeunit::STD => Timeunit::SR,
+            _ => Timeunit::KR,
+        }
+    }
+    pub fn longer(&self) -> Timeunit {
+        match self {
+            Timeunit::KR => Timeunit::SR,
+            _ => Timeunit::STD,
+        }
+    }
 }
 impl std::fmt::Display for Timeunit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -14,11 +49,6 @@ impl std::fmt::Display for Timeunit {
         }
     }
 }
-impl Default for Timeunit {
-    fn default() -> Self {
-        Timeunit::SR
-    }
-}
 
 #[derive(Serialize)]
 pub enum Format {
@@ -59,13 +89,30 @@ impl ValueEnum for Format {
     }
 }
 
-#[derive(Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DiceOverTime {
     pub dice: u32,
     pub flat: u32,
     pub time: Timeunit
 }
 impl DiceOverTime {
+    /// Baut aus einem Ausdruck wie `2W6+3` einen Zeitwert. Erlaubt sind nur W6 mit positivem Zuschlag.
+    pub fn from_dice(dice: &Dice, time: Timeunit) -> Result<Self, DiceError> {
+        if dice.sides != 6 || dice.factor != 1 || dice.modifier < 0 {
+            return Err(DiceError::NotW6(dice.to_string()));
+        }
+        Ok(DiceOverTime { dice: dice.count, flat: dice.modifier as u32, time })
+    }
+    pub fn to_dice(&self) -> Result<Dice, DiceError> {
+        let modifier = i32::try_from(self.flat).map_err(|_| DiceError::TooLarge(self.roll_only()))?;
+        let dice = Dice { modifier, ..Dice::new(self.dice, 6) };
+        dice.check()?;
+        Ok(dice)
+    }
+    pub fn roll(&self, rng: &mut impl Rng) -> Result<DiceRoll, DiceError> {
+        self.to_dice()?.roll(rng)
+    }
     pub fn roll_only(&self) -> String {
         format!("{}W6+{}", self.dice, self.flat)
     }
@@ -81,7 +128,7 @@ impl std::fmt::Display for DiceOverTime {
     }
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Characteristic {
     MU, KL, IN, CH, FF, GE, KO, KK
 }
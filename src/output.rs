@@ -0,0 +1,142 @@
+use std::io::Write;
+use serde::Serialize;
+use crate::Format;
+use crate::csv::{CsvRecord, CsvWriter};
+use crate::envelope::{Envelope, Labels, Provenance};
+
+/// Ein Datensatz, der sich in jedem `Format` ausgeben lässt. CSV und JSON kommen aus `CsvRecord` und `Labels`.
+pub trait Render: CsvRecord + Labels {
+    fn text(&self) -> String;
+    fn md(&self) -> String;
+}
+
+#[derive(Debug)]
+pub enum OutputError {
+    Open(std::path::PathBuf, std::io::Error),
+    Write(std::io::Error),
+    Json(serde_json::Error),
+    /// Die Kopfzeile der Datei, an die angehängt werden soll, passt nicht zu den neuen Zeilen
+    Header(std::path::PathBuf),
+    /// JSON lässt sich nicht anhängen, jede Datei enthält genau einen Umschlag
+    JsonAppend
+}
+impl OutputError {
+    /// Der Exit Code nach sysexits.h: 64 für `-a` mit JSON, 73 wenn die Datei nicht angelegt werden kann, sonst 74
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            OutputError::JsonAppend => 64,
+            OutputError::Open(_, _) | OutputError::Header(_) => 73,
+            OutputError::Write(_) | OutputError::Json(_) => 74,
+        }
+    }
+}
+impl std::fmt::Display for OutputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputError::Open(path, e) => write!(f, "Kann {} nicht zum Schreiben öffnen: {}", path.display(), e),
+            OutputError::Write(e) => write!(f, "Fehler beim Schreiben der Ausgabe: {}", e),
+            OutputError::Json(e) => write!(f, "Fehler beim Erzeugen des JSON: {}", e),
+            OutputError::Header(path) => write!(f, "Kann nicht an {} anhängen: Die Spalten passen nicht zur Kopfzeile der Datei", path.display()),
+            OutputError::JsonAppend => write!(f, "-a geht nicht mit -f json: Jede JSON Datei enthält genau einen Umschlag, damit --replay sie lesen kann."),
+        }
+    }
+}
+impl std::error::Error for OutputError {}
+impl From<std::io::Error> for OutputError {
+    fn from(e: std::io::Error) -> Self {
+        OutputError::Write(e)
+    }
+}
+
+/// Wohin und wie ausgegeben wird. Ohne Pfad geht alles nach stdout.
+pub struct Output {
+    pub format: Format,
+    pub path: Option<std::path::PathBuf>,
+    /// Hängt an eine bestehende Datei an, statt sie zu überschreiben. Bei CSV entfällt dann die zweite Kopfzeile.
+    pub append: bool,
+    pub delimiter: char,
+    pub origin: Provenance
+}
+impl Output {
+    pub fn new(format: Format, origin: Provenance) -> Self {
+        Output { format, path: None, append: false, delimiter: ',', origin }
+    }
+    pub fn with_file(mut self, path: Option<std::path::PathBuf>, append: bool) -> Self {
+        self.path = path;
+        self.append = append;
+        self
+    }
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+    /// Öffnet das Ziel
+    fn open(&self) -> Result<Box<dyn Write>, OutputError> {
+        let path = match &self.path {
+            Some(p) => p,
+            None => return Ok(Box::new(std::io::stdout())),
+        };
+        let file = if self.append {
+            std::fs::OpenOptions::new().create(true).append(true).open(path)
+        } else {
+            std::fs::File::create(path)
+        };
+        match file {
+            Ok(f) => Ok(Box::new(std::io::BufWriter::new(f))),
+            Err(e) => Err(OutputError::Open(path.clone(), e)),
+        }
+    }
+    /// Die Kopfzeile, wenn CSV an eine nicht leere Datei angehängt wird
+    fn existing_header(&self) -> Result<Option<Vec<String>>, OutputError> {
+        let path = match &self.path {
+            Some(p) if self.append && matches!(self.format, Format::CSV) => p,
+            _ => return Ok(None),
+        };
+        let file = match std::fs::File::open(path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(OutputError::Open(path.clone(), e)),
+        };
+        let mut line = String::new();
+        std::io::BufRead::read_line(&mut std::io::BufReader::new(file), &mut line).map_err(|e| OutputError::Open(path.clone(), e))?;
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(crate::csv::parse_row(&line, self.delimiter)))
+    }
+    /// Schreibt die Datensätze im gewählten Format. Text und Markdown bekommen Seed und Version als letzte Zeile,
+    /// CSV als eigene Spalten und JSON im Umschlag zusammen mit den Eingaben. JSON lässt sich nicht anhängen.
+    pub fn write<P: Serialize, T: Render>(&self, params: &P, records: &[T]) -> Result<(), OutputError> {
+        if self.append && matches!(self.format, Format::JSON) {
+            return Err(OutputError::JsonAppend);
+        }
+        let existing = self.existing_header()?;
+        let mut out = self.open()?;
+        match self.format {
+            Format::TEXT => writeln!(out, "{}\n\n{}", join(records.iter().map(|r| r.text()).collect()), self.origin)?,
+            Format::MD => writeln!(out, "{}\n\n{}", join(records.iter().map(|r| r.md()).collect()), self.origin.md())?,
+            Format::CSV => {
+                let mut csv = CsvWriter::new(&mut out, self.delimiter).with_columns(self.origin.columns());
+                match existing {
+                    Some(header) => {
+                        let width = csv.fits(records, &header).ok_or_else(|| OutputError::Header(self.path.clone().unwrap_or_default()))?;
+                        csv.write_rows(records, width)?;
+                    },
+                    None => csv.write_records(records)?,
+                }
+            },
+            Format::JSON => {
+                let envelope = Envelope::new(&self.origin, params, records);
+                serde_json::to_writer(&mut out, &envelope).map_err(OutputError::Json)?;
+                writeln!(out)?;
+            },
+        }
+        Ok(out.flush()?)
+    }
+}
+
+/// Mehrzeilige Datensätze werden durch eine Leerzeile getrennt, einzeilige stehen direkt untereinander
+fn join(parts: Vec<String>) -> String {
+    let separator = if parts.iter().any(|p| p.contains('\n')) {"\n\n"} else {"\n"};
+    parts.join(separator)
+}
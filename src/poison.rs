@@ -0,0 +1,606 @@
+use clap::{ValueEnum, builder::PossibleValue};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::{Characteristic, DiceOverTime, Timeunit};
+use crate::csv::{numbered, CsvRecord};
+use crate::dice::MAX;
+use crate::envelope::{unwrap_results, Labels};
+use crate::error::{read_json, Error};
+use crate::output::Render;
+
+#[derive(Debug)]
+pub enum PoisonError {
+    LevelOutOfRange(u32),
+    InvalidTable(String),
+    InvalidRange(String),
+    /// Mehr Würfel oder ein größerer Zuschlag als `dice::MAX`
+    TooLarge(String)
+}
+impl std::fmt::Display for PoisonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PoisonError::LevelOutOfRange(l) => write!(f, "Stufe war: {}\nMuss zwischen 1 und 20 liegen.", l),
+            PoisonError::InvalidTable(msg) => write!(f, "Ungültige Symptomtabelle: {}", msg),
+            PoisonError::InvalidRange(s) => write!(f, "Ungültiger Stufenbereich: \"{}\". Erwartet wird z.B. \"3..8\"", s),
+            PoisonError::TooLarge(s) => write!(f, "Höchstens {} W6 und ein Zuschlag bis {} erlaubt, war: \"{}\"", MAX, MAX, s),
+        }
+    }
+}
+impl std::error::Error for PoisonError {}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Symptom {
+    /// Eine feste englische Kennung wie `VOMITING`. Eigene Symptome können eine haben, `name` ist nur der Anzeigetext.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub amount: u32,
+    pub name: Box<str>,
+    #[serde(default)]
+    pub characteristic: Option<Characteristic>,
+    #[serde(default)]
+    pub disadvantage: Option<String>,
+    #[serde(default)]
+    pub unconsciousness: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub effects: Vec<String>
+}
+impl Symptom {
+    /// Würfelt ein Symptom nach der Tabelle im ZBA, S.217
+    pub fn roll(rng: &mut impl Rng) -> Result<Symptom, PoisonError> {
+        SymptomTable::default().roll(rng)
+    }
+}
+impl std::fmt::Display for Symptom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(c) = &self.characteristic {write!(f, " ({} -{}W6)", c, self.amount)?;}
+        if let Some(d) = &self.disadvantage {write!(f, " ({} +{}W6)", d, self.amount)?;}
+        if self.unconsciousness {write!(f, " (bewusstlos)")?;}
+        for e in &self.effects {write!(f, " ({})", e)?;}
+        Ok(())
+    }
+}
+
+/// Ein Eintrag der Symptomtabelle für die Würfe von `min` bis einschließlich `max`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SymptomEntry {
+    pub min: u32,
+    pub max: u32,
+    #[serde(flatten)]
+    pub symptom: Symptom
+}
+impl SymptomEntry {
+    fn new(min: u32, max: u32, symptom: Symptom) -> Self {
+        SymptomEntry { min, max, symptom }
+    }
+}
+
+/// Eine Symptomtabelle. Gewürfelt wird mit einem Würfel bis zum höchsten `max`, standardmäßig also W20.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SymptomTable {
+    pub entries: Vec<SymptomEntry>
+}
+impl SymptomTable {
+    /// Die Tabelle aus dem ZBA, S.217
+    pub fn builtin() -> Self {
+        SymptomTable { entries: vec![
+            SymptomEntry::new(1, 4, Symptom{id: Some("VOMITING".to_string()), name: "Erbrechen".into(), characteristic: Some(Characteristic::CH), ..Default::default()}),
+            SymptomEntry::new(5, 6, Symptom{id: Some("DIARRHEA".to_string()), name: "Durchfall / Koliken".into(), characteristic: Some(Characteristic::MU), ..Default::default()}),
+            SymptomEntry::new(7, 8, Symptom{id: Some("SWEATING".to_string()), name: "Schweißausbrüche / Atemnot".into(), characteristic: Some(Characteristic::KO), ..Default::default()}),
+            SymptomEntry::new(9, 10, Symptom{id: Some("WEAKNESS".to_string()), name: "Schwäche".into(), characteristic: Some(Characteristic::KK), ..Default::default()}),
+            SymptomEntry::new(11, 12, Symptom{id: Some("HEADACHE".to_string()), name: "Kopfschmerz / Schwindel".into(), characteristic: Some(Characteristic::KL), ..Default::default()}),
+            SymptomEntry::new(13, 14, Symptom{id: Some("PARALYSIS".to_string()), name: "Lähmungen".into(), characteristic: Some(Characteristic::GE), ..Default::default()}),
+            SymptomEntry::new(15, 16, Symptom{id: Some("NUMBNESS".to_string()), name: "Taubheiten".into(), characteristic: Some(Characteristic::FF), ..Default::default()}),
+            SymptomEntry::new(17, 17, Symptom{id: Some("SWELLING".to_string()), name: "Schwellungen".into(), characteristic: Some(Characteristic::GE), ..Default::default()}),
+            SymptomEntry::new(18, 18, Symptom{id: Some("AGITATION".to_string()), name: "Erregung".into(), disadvantage: Some("Jähzorn".to_string()), ..Default::default()}),
+            SymptomEntry::new(19, 19, Symptom{id: Some("BLEEDING".to_string()), name: "Blutungen".into(), disadvantage: Some("Aberglaube".to_string()), ..Default::default()}),
+            SymptomEntry::new(20, 20, Symptom{id: Some("UNCONSCIOUSNESS".to_string()), name: "Bewusstlosigkeit".into(), unconsciousness: true, ..Default::default()}),
+        ] }
+    }
+    /// Liest eine Tabelle als JSON Liste von Einträgen und prüft, dass jeder Wurf abgedeckt ist
+    pub fn load(path: &std::path::Path) -> crate::error::Result<Self> {
+        let table = SymptomTable { entries: read_json(path)? };
+        table.validate()?;
+        Ok(table)
+    }
+    pub fn validate(&self) -> Result<(), PoisonError> {
+        if self.entries.is_empty() {
+            return Err(PoisonError::InvalidTable("keine Einträge".to_string()));
+        }
+        if let Some(e) = self.entries.iter().find(|e| e.max == 0 || e.min > e.max) {
+            return Err(PoisonError::InvalidTable(format!("der Eintrag \"{}\" reicht von {} bis {}", e.symptom.name, e.min, e.max)));
+        }
+        for roll in 1..=self.die() {
+            match self.entries.iter().filter(|e| e.min <= roll && roll <= e.max).count() {
+                0 => return Err(PoisonError::InvalidTable(format!("kein Eintrag für den Wurf {}", roll))),
+                1 => (),
+                _ => return Err(PoisonError::InvalidTable(format!("mehrere Einträge für den Wurf {}", roll))),
+            }
+        }
+        Ok(())
+    }
+    pub fn die(&self) -> u32 {
+        self.entries.iter().map(|e| e.max).max().unwrap_or(1)
+    }
+    pub fn roll(&self, rng: &mut impl Rng) -> Result<Symptom, PoisonError> {
+        if self.die() == 0 {
+            return Err(PoisonError::InvalidTable("keine Einträge".to_string()));
+        }
+        let roll:u32 = rng.gen_range(1..=self.die());
+        match self.entries.iter().find(|e| e.min <= roll && roll <= e.max) {
+            Some(e) => Ok(e.symptom.clone()),
+            None => Err(PoisonError::InvalidTable(format!("kein Eintrag für den Wurf {}", roll)))
+        }
+    }
+}
+impl Default for SymptomTable {
+    fn default() -> Self {
+        SymptomTable::builtin()
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SymptomList {
+    pub symptoms: Vec<Symptom>
+}
+impl SymptomList {
+    pub fn push(&mut self, x: &mut Symptom) {
+        for s in &mut self.symptoms {
+            if s.name == x.name {s.amount += 1; return;}
+        }
+        x.amount = 1;
+        self.symptoms.push(x.clone());
+    }
+}
+impl std::fmt::Display for SymptomList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for s in 0..self.symptoms.len() {
+            write!(f, "{}", self.symptoms[s])?;
+            if s < (self.symptoms.len()-1) {write!(f, ", ")?;}
+        }
+        write!(f, "]")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum PoisonKind {
+    #[default]
+    INGESTED,
+    WEAPON,
+    INHALED,
+    CONTACT
+}
+impl ValueEnum for PoisonKind {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[PoisonKind::INGESTED, PoisonKind::WEAPON, PoisonKind::INHALED, PoisonKind::CONTACT]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            PoisonKind::INGESTED => PossibleValue::new("einnahme"),
+            PoisonKind::WEAPON => PossibleValue::new("waffen"),
+            PoisonKind::INHALED => PossibleValue::new("atem"),
+            PoisonKind::CONTACT => PossibleValue::new("kontakt"),
+        })
+    }
+}
+impl std::fmt::Display for PoisonKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PoisonKind::INGESTED => write!(f, "Einnahmegift"),
+            PoisonKind::WEAPON => write!(f, "Waffengift"),
+            PoisonKind::INHALED => write!(f, "Atemgift"),
+            PoisonKind::CONTACT => write!(f, "Kontaktgift"),
+        }
+    }
+}
+
+/// Woraus ein Gift gewonnen wird. Davon hängt ab, mit welchem Talent es leichter zu erkennen ist.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum PoisonOrigin {
+    #[default]
+    PLANT,
+    ANIMAL,
+    ALCHEMICAL
+}
+impl std::fmt::Display for PoisonOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PoisonOrigin::PLANT => write!(f, "Pflanzengift"),
+            PoisonOrigin::ANIMAL => write!(f, "Tiergift"),
+            PoisonOrigin::ALCHEMICAL => write!(f, "alchimistisches Gift"),
+        }
+    }
+}
+
+/// Die Verbreitung von 1 (einzigartig) bis 7 (sehr häufig)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Availability(pub u32);
+impl Availability {
+    pub fn from_level(level: u32) -> Availability {
+        Availability(8u32.saturating_sub(level.div_ceil(3)).clamp(1, 7))
+    }
+}
+impl std::fmt::Display for Availability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self.0 {
+            7.. => "sehr häufig",
+            6 => "häufig",
+            5 => "verbreitet",
+            4 => "gelegentlich",
+            3 => "selten",
+            2 => "sehr selten",
+            _ => "einzigartig",
+        };
+        write!(f, "{} ({})", self.0, name)
+    }
+}
+
+/// Die Zuschläge auf die Proben, um das Gift zu erkennen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Identification {
+    pub tierkunde: u32,
+    pub pflanzenkunde: u32,
+    pub alchimie: u32
+}
+impl Identification {
+    /// Mit dem Talent, das zur Herkunft passt, gilt die Stufe, mit dem anderen Kundetalent ist es um 5 schwerer.
+    /// Alchimistische Gifte sind mit beiden Kundetalenten schwer zu erkennen. Alchimie hilft immer mit der halben Stufe.
+    pub fn from_level(level: u32, origin: PoisonOrigin) -> Identification {
+        let (tierkunde, pflanzenkunde) = match origin {
+            PoisonOrigin::PLANT => (level + 5, level),
+            PoisonOrigin::ANIMAL => (level, level + 5),
+            PoisonOrigin::ALCHEMICAL => (level + 5, level + 5),
+        };
+        Identification { tierkunde, pflanzenkunde, alchimie: level.div_ceil(2) }
+    }
+}
+impl std::fmt::Display for Identification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Tierkunde +{}, Pflanzenkunde +{}, Alchimie +{}", self.tierkunde, self.pflanzenkunde, self.alchimie)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Poison {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub level: u32,
+    pub kind: PoisonKind,
+    /// Fehlt die Herkunft, gilt das Gift wie die aus dem ZBA als Pflanzengift
+    #[serde(default)]
+    pub origin: PoisonOrigin,
+    pub start: DiceOverTime,
+    pub damage: DiceOverTime,
+    pub duration: DiceOverTime,
+    pub symptoms: SymptomList,
+    /// Der Preis in Dukaten pro Dosis
+    #[serde(default)]
+    pub price: u32,
+    #[serde(default)]
+    pub availability: Availability,
+    #[serde(default)]
+    pub identification: Identification,
+    /// Der Zuschlag auf die Alchimie-Probe für das Gegengift
+    #[serde(default)]
+    pub antidote: u32
+}
+impl Poison {
+    /// Liest ein Gift, wie es mit `-f json` ausgegeben wurde. Bei mehreren Giften im Umschlag zählt das erste.
+    pub fn load(path: &std::path::Path) -> crate::error::Result<Poison> {
+        let value = match unwrap_results(read_json(path)?) {
+            serde_json::Value::Array(mut a) if !a.is_empty() => a.swap_remove(0),
+            v => v,
+        };
+        let poison: Poison = serde_json::from_value(value).map_err(|e| Error::Json(path.to_path_buf(), e))?;
+        poison.validate().map_err(|e| invalid(path, &poison, e))?;
+        Ok(poison.appraised())
+    }
+    /// Prüft ein Gift, das nicht vom Generator stammt: Stufe 1 bis 20, höchstens `dice::MAX` Würfel und Zuschlag, auch bei den Symptomen
+    pub fn validate(&self) -> Result<(), PoisonError> {
+        if !(1..=20).contains(&self.level) {
+            return Err(PoisonError::LevelOutOfRange(self.level));
+        }
+        if let Some(d) = [&self.start, &self.damage, &self.duration].into_iter().find(|d| d.dice > MAX || d.flat > MAX) {
+            return Err(PoisonError::TooLarge(d.to_string()));
+        }
+        if let Some(s) = self.symptoms.symptoms.iter().find(|s| s.amount > MAX) {
+            return Err(PoisonError::TooLarge(s.to_string()));
+        }
+        Ok(())
+    }
+    /// Zufallsgenerator für Gift beliebiger Stufe nach ZBA, S.217
+    pub fn generate(level: u32, rng: &mut impl Rng) -> Result<Poison, PoisonError> {
+        Poison::generate_with(level, &SymptomTable::default(), rng)
+    }
+    /// Wie `generate`, aber die Symptome kommen aus der übergebenen Tabelle
+    pub fn generate_with(level: u32, table: &SymptomTable, rng: &mut impl Rng) -> Result<Poison, PoisonError> {
+        if !(1..=20).contains(&level) {
+            return Err(PoisonError::LevelOutOfRange(level));
+        }
+
+        let dice = (level as f64/ 4.0).ceil() as u32;
+        let (start, damage, duration) = match level {
+            1..=5 => (
+                DiceOverTime{dice: 1, ..Default::default()},
+                DiceOverTime{dice: 1, time: Timeunit::STD, ..Default::default()},
+                DiceOverTime{flat: dice, ..Default::default()},
+            ),
+            6..=9 => (
+                DiceOverTime{flat: 1, ..Default::default()},
+                DiceOverTime{dice: 1, ..Default::default()},
+                DiceOverTime{flat: dice, ..Default::default()},
+            ),
+            10..=15 => (
+                DiceOverTime{dice: 1, flat: 4, time: Timeunit::KR},
+                DiceOverTime{dice: 2, ..Default::default()},
+                DiceOverTime{flat: dice/2, ..Default::default()},
+            ),
+            _ => (
+                DiceOverTime{dice: 1, time: Timeunit::KR, ..Default::default()},
+                DiceOverTime{dice: 1, time: Timeunit::KR, ..Default::default()},
+                DiceOverTime{flat: dice, time: Timeunit::KR, ..Default::default()},
+            ),
+        };
+
+        let mut symptoms = SymptomList::default();
+        for _ in 0..(level as f64/ 2.0).ceil() as u32 {
+            symptoms.push(&mut table.roll(rng)?)
+        }
+
+        let mut poison = Poison{name: None, level, kind: PoisonKind::INGESTED, origin: PoisonOrigin::PLANT, start, damage, duration, symptoms,
+            price: 0, availability: Availability::from_level(level), identification: Identification::from_level(level, PoisonOrigin::PLANT), antidote: level};
+        poison.price = poison.appraise();
+        Ok(poison)
+    }
+    /// Ergänzt Preis, Verbreitung, Erkennen und Gegengift aus der Stufe, wo sie fehlen
+    pub fn appraised(mut self) -> Poison {
+        if self.price == 0 {self.price = self.appraise();}
+        if self.availability == Availability::default() {self.availability = Availability::from_level(self.level);}
+        if self.identification == Identification::default() {self.identification = Identification::from_level(self.level, self.origin);}
+        if self.antidote == 0 {self.antidote = self.level;}
+        self
+    }
+    fn title(&self) -> String {
+        match &self.name {
+            Some(n) => format!("{} ({})", n, self.kind),
+            None => self.kind.to_string(),
+        }
+    }
+    /// Fünf Dukaten mal Stufe zum Quadrat, Waffen- und Kontaktgifte kosten das Anderthalbfache, Atemgifte das Doppelte
+    fn appraise(&self) -> u32 {
+        let base = 5 * self.level * self.level;
+        match self.kind {
+            PoisonKind::INGESTED => base,
+            PoisonKind::WEAPON | PoisonKind::CONTACT => base * 3 / 2,
+            PoisonKind::INHALED => base * 2,
+        }
+    }
+    /// Passt Beginn und Dauer an die Art des Gifts an. Ausgangspunkt ist ein Einnahmegift.
+    /// Waffengifte wirken eine Zeiteinheit schneller, Atemgifte sofort und kürzer, Kontaktgifte eine Zeiteinheit langsamer.
+    pub fn with_kind(mut self, kind: PoisonKind) -> Poison {
+        match kind {
+            PoisonKind::INGESTED => (),
+            PoisonKind::WEAPON => self.start.time = self.start.time.shorter(),
+            PoisonKind::INHALED => {
+                self.start = DiceOverTime{flat: 1, time: Timeunit::KR, ..Default::default()};
+                self.duration.flat = (self.duration.flat / 2).max(1);
+            },
+            PoisonKind::CONTACT => self.start.time = self.start.time.longer(),
+        }
+        self.kind = kind;
+        self.price = self.appraise();
+        self
+    }
+    /// Erzeugt `count` Gifte mit zufälliger Stufe aus dem Bereich, alle von der gleichen Art
+    pub fn generate_batch(count: u32, levels: &LevelRange, kind: PoisonKind, table: &SymptomTable, rng: &mut impl Rng) -> Result<Vec<Poison>, PoisonError> {
+        (0..count)
+            .map(|_| {
+                let level = rng.gen_range(levels.min..=levels.max);
+                Poison::generate_with(level, table, rng).map(|p| p.with_kind(kind))
+            })
+            .collect()
+    }
+}
+impl std::fmt::Display for Poison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\nStufe {}\nBeginn nach {}\nDauer {}\nSchaden {} pro {}\n{}\nPreis {} D\nVerbreitung {}\nErkennen als {}: {}\nGegengift: Alchimie +{}", self.title(), self.level, self.start, self.duration, self.damage.roll_only(), self.damage.time, self.symptoms,
+            self.price, self.availability, self.origin, self.identification, self.antidote)
+    }
+}
+
+impl CsvRecord for Poison {
+    fn header(records: &[Self]) -> Vec<String> {
+        let mut header: Vec<String> = ["Name", "Stufe", "Art", "Herkunft", "Beginn", "Schaden", "Intervall", "Dauer", "Preis", "Verbreitung", "Tierkunde", "Pflanzenkunde", "Alchimie", "Gegengift"]
+            .iter().map(|h| h.to_string()).collect();
+        header.extend(numbered("Symptom", records.iter().map(|p| p.symptoms.symptoms.len()).max().unwrap_or_default()));
+        header
+    }
+    fn fields(&self) -> Vec<String> {
+        let mut fields = vec![self.name.clone().unwrap_or_default(), self.level.to_string(), self.kind.to_string(), self.origin.to_string(), self.start.to_string(),
+            self.damage.roll_only(), self.damage.time.to_string(), self.duration.to_string(), self.price.to_string(), self.availability.0.to_string(),
+            self.identification.tierkunde.to_string(), self.identification.pflanzenkunde.to_string(), self.identification.alchimie.to_string(), self.antidote.to_string()];
+        fields.extend(self.symptoms.symptoms.iter().map(|s| s.to_string()));
+        fields
+    }
+}
+
+impl Render for Poison {
+    fn text(&self) -> String {
+        self.to_string()
+    }
+    fn md(&self) -> String {
+        format!("- {}\n- Stufe {}\n- Beginn nach {}\n- Dauer {}\n- Schaden {} pro {}\n- {}\n- Preis {} D\n- Verbreitung {}\n- Erkennen als {}: {}\n- Gegengift: Alchimie +{}", self.title(), self.level, self.start, self.duration, self.damage.roll_only(), self.damage.time, self.symptoms,
+            self.price, self.availability, self.origin, self.identification, self.antidote)
+    }
+}
+impl Labels for Poison {
+    fn labels(&self) -> serde_json::Value {
+        serde_json::json!({
+            "title": self.title(),
+            "kind": self.kind.to_string(),
+            "origin": self.origin.to_string(),
+            "start": self.start.to_string(),
+            "damage": format!("{} pro {}", self.damage.roll_only(), self.damage.time),
+            "duration": self.duration.to_string(),
+            "symptoms": self.symptoms.symptoms.iter().map(|s| s.to_string()).collect::<Vec<String>>(),
+            "price": format!("{} D", self.price),
+            "availability": self.availability.to_string(),
+            "identification": self.identification.to_string(),
+            "antidote": format!("Alchimie +{}", self.antidote),
+        })
+    }
+}
+
+/// Ein Gift in der Liste der Sammlung: als Text und Markdown nur eine Zeile, als CSV und JSON vollständig
+#[derive(Clone, Serialize)]
+#[serde(transparent)]
+pub struct Listing(pub Poison);
+impl Render for Listing {
+    fn text(&self) -> String {
+        format!("{}: {}, Stufe {}", self.0.name.as_deref().unwrap_or_default(), self.0.kind, self.0.level)
+    }
+    fn md(&self) -> String {
+        format!("- {} ({}, Stufe {})", self.0.name.as_deref().unwrap_or_default(), self.0.kind, self.0.level)
+    }
+}
+impl CsvRecord for Listing {
+    fn header(records: &[Self]) -> Vec<String> {
+        let poisons: Vec<Poison> = records.iter().map(|l| l.0.clone()).collect();
+        Poison::header(&poisons)
+    }
+    fn fields(&self) -> Vec<String> {
+        self.0.fields()
+    }
+}
+impl Labels for Listing {
+    fn labels(&self) -> serde_json::Value {
+        self.0.labels()
+    }
+}
+
+/// Ein Bereich von Giftstufen, z.B. "3..8" einschließlich beider Grenzen
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LevelRange {
+    pub min: u32,
+    pub max: u32
+}
+impl LevelRange {
+    pub fn single(level: u32) -> LevelRange {
+        LevelRange { min: level, max: level }
+    }
+}
+impl std::str::FromStr for LevelRange {
+    type Err = PoisonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || PoisonError::InvalidRange(s.to_string());
+        let (min, max) = match s.split_once("..") {
+            Some((min, max)) => (min.trim(), max.trim().trim_start_matches('=')),
+            None => (s.trim(), s.trim()),
+        };
+        let min: u32 = min.parse().map_err(|_| invalid())?;
+        let max: u32 = max.parse().map_err(|_| invalid())?;
+        if min > max {
+            return Err(invalid());
+        }
+        for level in [min, max] {
+            if !(1..=20).contains(&level) {
+                return Err(PoisonError::LevelOutOfRange(level));
+            }
+        }
+        Ok(LevelRange { min, max })
+    }
+}
+
+/// Ein ungültiges Gift aus einer Datei wird als Datenfehler mit dem Pfad gemeldet
+fn invalid(path: &std::path::Path, poison: &Poison, e: PoisonError) -> Error {
+    let msg = match &poison.name {
+        Some(n) => format!("{}: {}", n, e),
+        None => e.to_string(),
+    };
+    Error::Json(path.to_path_buf(), serde::de::Error::custom(msg))
+}
+
+/// Eine Sammlung benannter Gifte. Die eingebaute Sammlung bildet bekannte Gifte mit den Mitteln dieses Tools nach.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Catalogue {
+    pub poisons: Vec<Poison>
+}
+impl Catalogue {
+    pub fn builtin() -> Catalogue {
+        match serde_json::from_str::<Vec<Poison>>(include_str!("../data/gifte.json")) {
+            Ok(poisons) => Catalogue { poisons: poisons.into_iter().map(|p| p.appraised()).collect() },
+            Err(e) => panic!("{}", e)
+        }
+    }
+    /// Liest eine JSON Liste von Giften, auch im Umschlag von `-f json`. Fehlende Preise und Proben werden aus der Stufe ergänzt.
+    /// Ungültige Gifte lehnt sie wie `Poison::load` ab.
+    pub fn load(path: &std::path::Path) -> crate::error::Result<Catalogue> {
+        let poisons: Vec<Poison> = serde_json::from_value(unwrap_results(read_json(path)?)).map_err(|e| Error::Json(path.to_path_buf(), e))?;
+        for p in &poisons {
+            p.validate().map_err(|e| invalid(path, p, e))?;
+        }
+        Ok(Catalogue { poisons: poisons.into_iter().map(|p| p.appraised()).collect() })
+    }
+    /// Gifte mit gleichem Namen werden durch die neuen ersetzt
+    pub fn merge(&mut self, other: Catalogue) {
+        for p in other.poisons {
+            self.poisons.retain(|q| q.name.is_none() || !q.name.as_deref().unwrap_or_default().eq_ignore_ascii_case(p.name.as_deref().unwrap_or_default()));
+            self.poisons.push(p);
+        }
+    }
+    /// Sucht zuerst nach dem genauen Namen, dann nach einem eindeutigen Teil davon.
+    /// Passt der Teil auf mehrere Gifte, nennt der Fehler alle.
+    pub fn find(&self, name: &str) -> crate::error::Result<&Poison> {
+        let lower = name.to_lowercase();
+        let named = || self.poisons.iter().filter_map(|p| p.name.as_ref().map(|n| (n.to_lowercase(), p)));
+        if let Some((_, p)) = named().find(|(n, _)| *n == lower) {
+            return Ok(p);
+        }
+        let matches: Vec<&Poison> = named().filter(|(n, _)| n.contains(&lower)).map(|(_, p)| p).collect();
+        match matches.as_slice() {
+            [] => Err(Error::UnknownPoison(name.to_string())),
+            [p] => Ok(p),
+            _ => Err(Error::AmbiguousPoison(name.to_string(), matches.iter().filter_map(|p| p.name.clone()).collect())),
+        }
+    }
+    pub fn by_level(&self, level: u32) -> Vec<&Poison> {
+        self.poisons.iter().filter(|p| p.level == level).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_catalogue_parses_and_is_valid() {
+        let catalogue = Catalogue::builtin();
+        assert!(!catalogue.poisons.is_empty());
+        for p in &catalogue.poisons {
+            assert!(p.name.is_some());
+            assert!(p.validate().is_ok(), "{}", p.title());
+        }
+    }
+
+    #[test]
+    fn identification_follows_the_origin() {
+        let catalogue = Catalogue::builtin();
+        let arachnae = catalogue.find("Arachnae").unwrap();
+        assert_eq!(arachnae.origin, PoisonOrigin::ANIMAL);
+        assert!(arachnae.identification.tierkunde < arachnae.identification.pflanzenkunde);
+        assert_eq!(Identification::from_level(8, PoisonOrigin::PLANT), Identification { tierkunde: 13, pflanzenkunde: 8, alchimie: 4 });
+    }
+
+    #[test]
+    fn load_rejects_levels_out_of_range() {
+        let path = std::env::temp_dir().join(format!("dsa-tools-catalogue-{}.json", std::process::id()));
+        std::fs::write(&path, r#"[{"name": "Zu stark", "level": 40000, "kind": "INGESTED", "start": {}, "damage": {}, "duration": {}, "symptoms": {"symptoms": []}}]"#).unwrap();
+        let result = Catalogue::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(Error::Json(_, _))));
+    }
+}
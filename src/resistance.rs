@@ -0,0 +1,143 @@
+use clap::Args;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::csv::{numbered, CsvRecord};
+use crate::envelope::Labels;
+use crate::output::Render;
+use crate::poison::{Poison, SymptomList};
+
+/// Wie stark ein Gift nach der Widerstandsprobe wirkt
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Effect {
+    #[default]
+    FULL,
+    HALF,
+    NONE
+}
+impl Effect {
+    /// Halbiert bei halber Wirkung abgerundet
+    pub fn apply(&self, value: i32) -> i32 {
+        match self {
+            Effect::FULL => value,
+            Effect::HALF => value / 2,
+            Effect::NONE => 0,
+        }
+    }
+}
+impl std::fmt::Display for Effect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Effect::FULL => write!(f, "volle Wirkung"),
+            Effect::HALF => write!(f, "halbe Wirkung"),
+            Effect::NONE => write!(f, "keine Wirkung"),
+        }
+    }
+}
+
+impl SymptomList {
+    /// Die Symptome, die bei der Wirkung noch auftreten. Bei halber Wirkung tritt keine Bewusstlosigkeit auf,
+    /// von den übrigen Symptomen nur die erste Hälfte (aufgerundet) mit halbierter Menge (aufgerundet).
+    pub fn under(&self, effect: Effect) -> SymptomList {
+        let symptoms = match effect {
+            Effect::FULL => self.symptoms.clone(),
+            Effect::HALF => {
+                let remaining: Vec<_> = self.symptoms.iter().filter(|s| !s.unconsciousness).collect();
+                remaining.iter()
+                    .take(remaining.len().div_ceil(2))
+                    .map(|s| {let mut s = (*s).clone(); s.amount = s.amount.div_ceil(2); s})
+                    .collect()
+            },
+            Effect::NONE => Vec::new(),
+        };
+        SymptomList { symptoms }
+    }
+}
+
+/// Die Werte und Vorteile, mit denen sich ein Held gegen ein Gift wehrt
+#[derive(Args, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Resistance {
+    #[arg(long = "ko", help = "Die Konstitution des Opfers.")]
+    pub ko: i32,
+    #[arg(long = "giftresistenz", default_value_t = false,
+        help = "Resistenz gegen Gift: Die Stufe zählt für die Probe nur halb.")]
+    pub resistant: bool,
+    #[arg(long = "immunitaet", default_value_t = false,
+        help = "Immunität gegen Gift: Das Gift wirkt nicht.")]
+    pub immune: bool,
+    #[arg(long = "zaeh", default_value_t = false,
+        help = "Zäher Hund: Die Probe ist um 3 erleichtert.")]
+    pub tough: bool,
+    #[arg(long = "mod", default_value_t = 0, allow_negative_numbers = true,
+        help = "Sonstige Erleichterung (positiv) oder Erschwernis (negativ) der Probe.")]
+    pub modifier: i32
+}
+
+/// Das Ergebnis der KO-Probe gegen ein Gift
+#[derive(Clone, Debug, Serialize)]
+pub struct ResistanceCheck {
+    pub roll: u32,
+    pub target: i32,
+    pub effect: Effect,
+    pub symptoms: SymptomList
+}
+impl ResistanceCheck {
+    fn roll_str(&self) -> String {
+        match self.roll {
+            0 => "Immunität gegen Gift".to_string(),
+            r => format!("KO-Probe: {} gegen {}", r, self.target),
+        }
+    }
+}
+impl CsvRecord for ResistanceCheck {
+    fn header(records: &[Self]) -> Vec<String> {
+        let mut header: Vec<String> = ["Wurf", "Ziel", "Wirkung"].iter().map(|h| h.to_string()).collect();
+        header.extend(numbered("Symptom", records.iter().map(|c| c.symptoms.symptoms.len()).max().unwrap_or_default()));
+        header
+    }
+    fn fields(&self) -> Vec<String> {
+        let mut fields = vec![self.roll.to_string(), self.target.to_string(), self.effect.to_string()];
+        fields.extend(self.symptoms.symptoms.iter().map(|s| s.to_string()));
+        fields
+    }
+}
+impl Render for ResistanceCheck {
+    fn text(&self) -> String {
+        self.to_string()
+    }
+    fn md(&self) -> String {
+        format!("- {}\n- {}\n- {}", self.roll_str(), self.effect, self.symptoms)
+    }
+}
+impl Labels for ResistanceCheck {
+    fn labels(&self) -> serde_json::Value {
+        serde_json::json!({
+            "roll": self.roll_str(),
+            "effect": self.effect.to_string(),
+            "symptoms": self.symptoms.symptoms.iter().map(|s| s.to_string()).collect::<Vec<String>>(),
+        })
+    }
+}
+impl std::fmt::Display for ResistanceCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\n{}\n{}", self.roll_str(), self.effect, self.symptoms)
+    }
+}
+
+/// Würfelt die KO-Probe erschwert um die Giftstufe. Gelingt sie, wirkt das Gift nur halb, bei einer 1 gar nicht.
+/// Eine 20 misslingt immer. Mit Immunität wird nicht gewürfelt.
+pub fn resist(poison: &Poison, resistance: &Resistance, rng: &mut impl Rng) -> ResistanceCheck {
+    let level = if resistance.resistant {poison.level as i32 / 2} else {poison.level as i32};
+    let target = resistance.ko - level + resistance.modifier + if resistance.tough {3} else {0};
+    if resistance.immune {
+        return ResistanceCheck { roll: 0, target, effect: Effect::NONE, symptoms: poison.symptoms.under(Effect::NONE) };
+    }
+
+    let roll: u32 = rng.gen_range(1..=20);
+    let effect = match roll {
+        1 => Effect::NONE,
+        20 => Effect::FULL,
+        r if r as i32 <= target => Effect::HALF,
+        _ => Effect::FULL,
+    };
+    ResistanceCheck { roll, target, effect, symptoms: poison.symptoms.under(effect) }
+}
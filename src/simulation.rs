@@ -0,0 +1,174 @@
+use rand::Rng;
+use serde::Serialize;
+use crate::{Characteristic, Timeunit};
+use crate::csv::{numbered, CsvRecord};
+use crate::envelope::Labels;
+use crate::error::Result;
+use crate::output::Render;
+use crate::poison::Poison;
+use crate::resistance::Effect;
+
+fn format_kr(kr: u32) -> String {
+    if kr > 0 && kr.is_multiple_of(Timeunit::STD.in_kr()) {
+        format!("{} {}", kr / Timeunit::STD.in_kr(), Timeunit::STD)
+    } else if kr > 0 && kr.is_multiple_of(Timeunit::SR.in_kr()) {
+        format!("{} {}", kr / Timeunit::SR.in_kr(), Timeunit::SR)
+    } else {
+        format!("{} {}", kr, Timeunit::KR)
+    }
+}
+
+/// Das Opfer eines Gifts
+#[derive(Clone, Debug, Serialize)]
+pub struct Victim {
+    pub lep: i32,
+    pub ko: i32
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum Condition {
+    OK,
+    UNCONSCIOUS,
+    DEAD
+}
+impl std::fmt::Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Condition::OK => write!(f, "bei Bewusstsein"),
+            Condition::UNCONSCIOUS => write!(f, "bewusstlos"),
+            Condition::DEAD => write!(f, "tot"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Penalty {
+    pub characteristic: Characteristic,
+    pub amount: u32
+}
+impl std::fmt::Display for Penalty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -{}", self.characteristic, self.amount)
+    }
+}
+
+/// Ein Zeitpunkt im Verlauf einer Vergiftung
+#[derive(Clone, Debug, Serialize)]
+pub struct Event {
+    pub kr: u32,
+    pub event: String,
+    pub lep: i32,
+    pub ko: i32,
+    pub penalties: Vec<Penalty>,
+    pub condition: Condition
+}
+impl Event {
+    fn penalties_str(&self) -> String {
+        let p: Vec<String> = self.penalties.iter().map(|p| p.to_string()).collect();
+        p.join(", ")
+    }
+}
+impl CsvRecord for Event {
+    fn header(records: &[Self]) -> Vec<String> {
+        let mut header: Vec<String> = ["Zeit", "Ereignis", "LeP", "KO", "Zustand"].iter().map(|h| h.to_string()).collect();
+        header.extend(numbered("Abzug", records.iter().map(|e| e.penalties.len()).max().unwrap_or_default()));
+        header
+    }
+    fn fields(&self) -> Vec<String> {
+        let mut fields = vec![format_kr(self.kr), self.event.clone(), self.lep.to_string(), self.ko.to_string(), self.condition.to_string()];
+        fields.extend(self.penalties.iter().map(|p| p.to_string()));
+        fields
+    }
+}
+impl Render for Event {
+    fn text(&self) -> String {
+        self.to_string()
+    }
+    fn md(&self) -> String {
+        format!("- nach {}: {}, LeP {}, KO {}, {}{}", format_kr(self.kr), self.event, self.lep, self.ko, self.condition,
+            if self.penalties.is_empty() {String::new()} else {format!(", {}", self.penalties_str())})
+    }
+}
+impl Labels for Event {
+    fn labels(&self) -> serde_json::Value {
+        serde_json::json!({
+            "time": format_kr(self.kr),
+            "condition": self.condition.to_string(),
+            "penalties": self.penalties.iter().map(|p| p.to_string()).collect::<Vec<String>>(),
+        })
+    }
+}
+impl std::fmt::Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "nach {}: {}\nLeP {}, KO {}, {}", format_kr(self.kr), self.event, self.lep, self.ko, self.condition)?;
+        if !self.penalties.is_empty() {write!(f, "\n{}", self.penalties_str())?;}
+        Ok(())
+    }
+}
+
+/// Spielt die Wirkung eines Gifts durch: Beginn, Schaden je Intervall und Symptome, bis die Wirkung endet oder das Opfer stirbt.
+/// Bei 0 LeP oder weniger ist das Opfer bewusstlos, bei -KO tot. Ein Abzug auf KO durch Symptome senkt diese Grenze.
+pub fn simulate(poison: &Poison, victim: &Victim, rng: &mut impl Rng) -> Result<Vec<Event>> {
+    simulate_with(poison, victim, Effect::FULL, rng)
+}
+/// Wie `simulate`, aber mit der Wirkung nach einer Widerstandsprobe. Bei halber Wirkung halbieren sich Schaden und Symptome.
+pub fn simulate_with(poison: &Poison, victim: &Victim, effect: Effect, rng: &mut impl Rng) -> Result<Vec<Event>> {
+    if effect == Effect::NONE {
+        return Ok(vec![Event { kr: 0, event: format!("Vergiftung, {}", effect), lep: victim.lep, ko: victim.ko, penalties: Vec::new(), condition: Condition::OK }]);
+    }
+    let onset = (poison.start.roll(rng)?.total.max(0) as u32).saturating_mul(poison.start.time.in_kr());
+    let duration = (poison.duration.roll(rng)?.total.max(1) as u32).saturating_mul(poison.duration.time.in_kr()).max(1);
+    let end = onset.saturating_add(duration);
+    let interval = poison.damage.time.in_kr();
+
+    let mut lep = victim.lep;
+    let mut ko = victim.ko;
+    let mut unconscious = false;
+    let mut penalties: Vec<Penalty> = Vec::new();
+    let mut effects: Vec<String> = Vec::new();
+    let symptom_list = poison.symptoms.under(effect);
+    for s in &symptom_list.symptoms {
+        if let Some(c) = &s.characteristic {
+            let amount = (0..s.amount).map(|_| rng.gen_range(1..=6)).sum();
+            if let Characteristic::KO = c {ko -= amount as i32;}
+            match penalties.iter_mut().find(|p| &p.characteristic == c) {
+                Some(p) => p.amount += amount,
+                None => penalties.push(Penalty { characteristic: c.clone(), amount }),
+            }
+        }
+        if let Some(d) = &s.disadvantage {
+            let amount: u32 = (0..s.amount).map(|_| rng.gen_range(1..=6)).sum();
+            effects.push(format!("{} +{}", d, amount));
+        }
+        if s.unconsciousness {unconscious = true;}
+        effects.extend(s.effects.iter().cloned());
+    }
+
+    let condition = |lep: i32, ko: i32, unconscious: bool| {
+        if lep <= -ko {Condition::DEAD}
+        else if lep <= 0 || unconscious {Condition::UNCONSCIOUS}
+        else {Condition::OK}
+    };
+    let mut events = vec![Event { kr: 0, event: "Vergiftung".to_string(), lep, ko: victim.ko, penalties: Vec::new(), condition: condition(lep, victim.ko, false) }];
+
+    let mut symptoms = format!("Wirkung beginnt, Symptome: {}", symptom_list);
+    if effect == Effect::HALF {symptoms = format!("{}, {}", effect, symptoms);}
+    if !effects.is_empty() {symptoms = format!("{} ({})", symptoms, effects.join(", "));}
+    let mut t = onset;
+    let mut first = true;
+    while t < end {
+        let damage = effect.apply(poison.damage.roll(rng)?.total.max(0));
+        lep -= damage;
+        let event = if first {format!("{}, {} SP", symptoms, damage)} else {format!("{} SP", damage)};
+        let c = condition(lep, ko, unconscious);
+        events.push(Event { kr: t, event, lep, ko, penalties: penalties.clone(), condition: c });
+        if c == Condition::DEAD {
+            return Ok(events);
+        }
+        first = false;
+        t = t.saturating_add(interval);
+    }
+
+    events.push(Event { kr: end, event: "Wirkung endet".to_string(), lep, ko, penalties, condition: condition(lep, ko, false) });
+    Ok(events)
+}
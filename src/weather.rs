@@ -0,0 +1,1361 @@
+use clap::{ValueEnum, builder::PossibleValue};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::{DiceOverTime, Timeunit};
+use crate::calendar::{AvDate, Weekday};
+use crate::csv::CsvRecord;
+use crate::envelope::Labels;
+use crate::error::{read_json, write_json, Error, Result};
+use crate::output::Render;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Season {#[default] SUMMER,AUTUMN,WINTER,SPRING}
+impl ValueEnum for Season {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Season::SUMMER,Season::AUTUMN,Season::WINTER,Season::SPRING]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(format!("{}", self)))
+    }
+}
+impl std::fmt::Display for Season {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Season::SUMMER => write!(f, "Sommer"),
+            Season::AUTUMN => write!(f, "Herbst"),
+            Season::WINTER => write!(f, "Winter"),
+            Season::SPRING => write!(f, "Frühling"),
+        }
+    }
+}
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
+pub enum Region {
+    EWIGES_EIS,
+    EHERNES_SCHWERT,
+    HOHER_NORDEN,
+    TUNDRA,
+    THORWAL,
+    WEIDEN,
+    MITTELREICH,
+    ALMADA,
+    RASCHTULSWALL,
+    HORASREICH_SUED,
+    KHOM,
+    ECHSENSUEMPFE,
+    SUEDMEER,
+    CUSTOM(Box<CustomRegion>)
+}
+impl Region {
+    pub fn is_desert(&self) -> bool {
+        match self {
+            Region::CUSTOM(c) => c.desert,
+            _ => false,
+        }
+    }
+    pub fn is_windy(&self) -> bool {
+        match self {
+            Region::CUSTOM(c) => c.windy,
+            _ => false,
+        }
+    }
+    /// Die vorherrschende Windrichtung, falls die Region eine hat
+    pub fn prevailing_wind(&self) -> Option<Direction> {
+        match self {
+            Region::THORWAL => Some(Direction::WEST),
+            Region::SUEDMEER => Some(Direction::EAST),
+            _ => None,
+        }
+    }
+    pub fn temp_base (&self, season: &Season) -> i32 {
+        let tuple = match self {
+            Region::EWIGES_EIS => (-20,-30,-40),
+            Region::EHERNES_SCHWERT => (-10,-20,-30),
+            Region::HOHER_NORDEN => (0,-10,-20),
+            Region::TUNDRA => (5,0,-5),
+            Region::THORWAL => (10,3,-5),
+            Region::WEIDEN => (10,5,0),
+            Region::MITTELREICH => (15,10,5),
+            Region::ALMADA => (20,15,10),
+            Region::RASCHTULSWALL => (5,0,-10),
+            Region::HORASREICH_SUED => (25,20,15),
+            Region::KHOM => (40,35,30),
+            Region::ECHSENSUEMPFE => (30,25,20),
+            Region::SUEDMEER => (35,30,25),
+            Region::CUSTOM(c) => (c.summer, c.transitional, c.winter),
+        };
+        match season {
+            Season::SUMMER => tuple.0,
+            Season::WINTER => tuple.2,
+            Season::SPRING | Season::AUTUMN => tuple.1,
+        }
+    }
+}
+impl ValueEnum for Region {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Region::EWIGES_EIS, Region::EHERNES_SCHWERT, Region::HOHER_NORDEN,
+            Region::TUNDRA, Region::THORWAL, Region::WEIDEN,
+            Region::MITTELREICH, Region::ALMADA, Region::RASCHTULSWALL,
+            Region::HORASREICH_SUED, Region::KHOM, Region::ECHSENSUEMPFE,
+            Region::SUEDMEER]
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        Some(PossibleValue::new(format!("{}", self)))
+    }
+}
+impl std::fmt::Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Region::EWIGES_EIS => write!(f, "Ewiges Eis"),
+            Region::EHERNES_SCHWERT => write!(f, "Höhen des Ehernen Schwerts"),
+            Region::HOHER_NORDEN => write!(f, "Hoher Norden"),
+            Region::TUNDRA => write!(f, "Tundra und Taiga"),
+            Region::THORWAL => write!(f, "Bornland, Thorwal"),
+            Region::WEIDEN => write!(f, "Streitende Königreiche bis Weiden"),
+            Region::MITTELREICH => write!(f, "Zentrales Mittelreich"),
+            Region::ALMADA => write!(f, "Nördliches Horasreich, Almada, Aranien"),
+            Region::RASCHTULSWALL => write!(f, "Höhen des Raschtulswalls"),
+            Region::HORASREICH_SUED => write!(f, "Südliches Horasreich, Reich der ersten Sonne"),
+            Region::KHOM => write!(f, "Khom"),
+            Region::ECHSENSUEMPFE => write!(f, "Echsensümpfe, Meridiana"),
+            Region::SUEDMEER => write!(f, "Altoum, Gewürzinseln, Südmeer"),
+            Region::CUSTOM(c) => write!(f, "{}", c.name),
+        }
+    }
+}
+
+/// Ein Eintrag einer W20 Tabelle: gilt für alle Würfe bis einschließlich `max`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TableEntry<T> {
+    pub max: u32,
+    pub value: T
+}
+/// Würfe über dem letzten `max`, etwa 21 und 22 durch `-w`, zählen zum letzten Eintrag
+fn lookup<T: Copy>(table: &[TableEntry<T>], roll: u32) -> Option<T> {
+    table.iter().find(|e| roll <= e.max).or(table.last()).map(|e| e.value)
+}
+
+/// Eine selbst definierte Region, z.B. für Myranor. Leere Tabellen bedeuten die Tabellen aus dem WdE.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CustomRegion {
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub summer: i32,
+    pub transitional: i32,
+    pub winter: i32,
+    #[serde(default)]
+    pub desert: bool,
+    #[serde(default)]
+    pub windy: bool,
+    #[serde(default)]
+    pub clouds: Vec<TableEntry<Clouds>>,
+    #[serde(default)]
+    pub wind: Vec<TableEntry<Wind>>
+}
+
+impl CustomRegion {
+    /// Prüft, dass die Tabellen jeden Wurf von 1 bis 20 genau einmal abdecken
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        check_table("Wolken", &self.clouds)?;
+        check_table("Wind", &self.wind)
+    }
+}
+fn check_table<T>(name: &str, table: &[TableEntry<T>]) -> std::result::Result<(), String> {
+    if table.is_empty() {
+        return Ok(());
+    }
+    let mut from = 1;
+    for e in table {
+        if e.max < from {
+            return Err(format!("Die Tabelle {} ist nicht aufsteigend, der Eintrag bis {} folgt auf einen bis {}", name, e.max, from - 1));
+        }
+        from = e.max + 1;
+    }
+    if from <= 20 {
+        return Err(format!("Die Tabelle {} deckt nur die Würfe bis {} ab, nicht bis 20", name, from - 1));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct RouteEntry {
+    region: String,
+    days: u64,
+    #[serde(default)]
+    desert: bool
+}
+
+/// Die eingebauten Regionen, ergänzt um selbst definierte aus Dateien
+#[derive(Clone, Debug, Default)]
+pub struct Regions {
+    pub custom: Vec<CustomRegion>
+}
+impl Regions {
+    pub fn load(path: &std::path::Path) -> Result<Regions> {
+        let custom: Vec<CustomRegion> = read_json(path)?;
+        for c in &custom {
+            c.validate().map_err(|e| Error::InvalidRegion(path.to_path_buf(), format!("{}: {}", c.name, e)))?;
+        }
+        Ok(Regions { custom })
+    }
+    pub fn merge(&mut self, other: Regions) {
+        self.custom.extend(other.custom);
+    }
+    /// Sucht nach Name, Alias oder Bezeichner wie `KHOM`. Eigene Regionen haben Vorrang.
+    pub fn find(&self, name: &str) -> Option<Region> {
+        let name = name.trim().to_lowercase();
+        let custom = self.custom.iter().rev()
+            .find(|c| c.name.to_lowercase() == name || c.aliases.iter().any(|a| a.to_lowercase() == name));
+        if let Some(c) = custom {
+            return Some(Region::CUSTOM(Box::new(c.clone())));
+        }
+        Region::value_variants().iter()
+            .find(|r| format!("{:?}", r).to_lowercase() == name || r.to_string().to_lowercase() == name)
+            .cloned()
+    }
+    /// Erwartet `REGION:TAGE` oder `REGION:TAGE:wueste`, z.B. `KHOM:4:wueste`
+    pub fn leg(&self, s: &str) -> Result<Leg> {
+        let invalid = || Error::InvalidLeg(s.to_string());
+        let parts: Vec<&str> = s.split(':').map(|p| p.trim()).collect();
+        if parts.len() < 2 || parts.len() > 3 {
+            return Err(invalid());
+        }
+        let region = self.find(parts[0]).ok_or_else(|| Error::UnknownRegion(parts[0].to_string()))?;
+        let days = parts[1].parse().map_err(|_| invalid())?;
+        let desert = match parts.get(2).map(|p| p.to_lowercase()) {
+            None => false,
+            Some(p) if ["wueste", "wüste", "desert", "d"].contains(&p.as_str()) => true,
+            Some(_) => return Err(invalid()),
+        };
+        Ok(Leg { region, days, desert })
+    }
+    pub fn load_route(&self, path: &std::path::Path) -> Result<Vec<Leg>> {
+        let entries: Vec<RouteEntry> = read_json(path)?;
+        entries.into_iter().map(|e| match self.find(&e.region) {
+            Some(region) => Ok(Leg { region, days: e.days, desert: e.desert }),
+            None => Err(Error::UnknownRegion(e.region)),
+        }).collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Clouds {NONE,FEW,LOTS,ALL}
+impl Clouds {
+    pub fn temp_mod (&self) -> i32 {
+        match self {
+            Clouds::NONE => 10,
+            Clouds::FEW => 5,
+            Clouds::LOTS => 0,
+            Clouds::ALL => -5,
+        }
+    }
+}
+impl std::fmt::Display for Clouds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Clouds::NONE => write!(f, "völlig wolkenlos"),
+            Clouds::FEW => write!(f, "einzelne Wolken"),
+            Clouds::LOTS => write!(f, "bewölkt mit Wolkenlücken"),
+            Clouds::ALL => write!(f, "geschlossene Wolkendecke"),
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Wind {
+    NONE,
+    LIGHT,
+    SOFT,
+    FRESH,
+    COOL,
+    STRONG,
+    STORM,
+    /// Sturm in der Wüste
+    SANDSTORM
+}
+impl Wind {
+    pub fn temp_mod (&self) -> i32 {
+        match self {
+            Wind::NONE => 4,
+            Wind::LIGHT => 2,
+            Wind::SOFT | Wind::FRESH => 0,
+            Wind::COOL => -2,
+            Wind::STRONG => -4,
+            Wind::STORM | Wind::SANDSTORM => -6,
+        }
+    }
+    /// In der Wüste wird aus einem Sturm ein Sandsturm, außerhalb wieder ein gewöhnlicher Sturm
+    pub fn in_desert(self, desert: bool) -> Wind {
+        match self {
+            Wind::STORM if desert => Wind::SANDSTORM,
+            Wind::SANDSTORM if !desert => Wind::STORM,
+            w => w,
+        }
+    }
+}
+impl std::fmt::Display for Wind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Wind::NONE => write!(f, "windstill"),
+            Wind::LIGHT => write!(f, "leichter Wind"),
+            Wind::SOFT => write!(f, "sanfte Brise"),
+            Wind::FRESH => write!(f, "frische Brise"),
+            Wind::COOL => write!(f, "steife Brise"),
+            Wind::STRONG => write!(f, "starker Wind"),
+            Wind::STORM => write!(f, "Sturm"),
+            Wind::SANDSTORM => write!(f, "Sandsturm"),
+        }
+    }
+}
+/// Die Richtung, aus der der Wind kommt
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Direction {
+    #[default]
+    NORTH,
+    NORTHEAST,
+    EAST,
+    SOUTHEAST,
+    SOUTH,
+    SOUTHWEST,
+    WEST,
+    NORTHWEST
+}
+impl Direction {
+    pub const ALL: [Direction; 8] = [Direction::NORTH, Direction::NORTHEAST, Direction::EAST, Direction::SOUTHEAST,
+        Direction::SOUTH, Direction::SOUTHWEST, Direction::WEST, Direction::NORTHWEST];
+
+    fn index(&self) -> i32 {
+        Direction::ALL.iter().position(|d| d == self).unwrap_or_default() as i32
+    }
+    /// Dreht um so viele Achtel im Uhrzeigersinn, negative Werte drehen gegen den Uhrzeigersinn
+    pub fn turn(&self, eighths: i32) -> Direction {
+        Direction::ALL[(self.index() + eighths).rem_euclid(8) as usize]
+    }
+    /// Der Winkel zu einer anderen Richtung in Achteln, von 0 bis 4
+    pub fn eighths_to(&self, other: &Direction) -> u32 {
+        let diff = (self.index() - other.index()).rem_euclid(8) as u32;
+        diff.min(8 - diff)
+    }
+}
+impl ValueEnum for Direction {
+    fn value_variants<'a>() -> &'a [Self] {
+        &Direction::ALL
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Direction::NORTH => PossibleValue::new("nord").alias("n"),
+            Direction::NORTHEAST => PossibleValue::new("nordost").alias("no"),
+            Direction::EAST => PossibleValue::new("ost").alias("o"),
+            Direction::SOUTHEAST => PossibleValue::new("suedost").aliases(["südost", "so"]),
+            Direction::SOUTH => PossibleValue::new("sued").aliases(["süd", "s"]),
+            Direction::SOUTHWEST => PossibleValue::new("suedwest").aliases(["südwest", "sw"]),
+            Direction::WEST => PossibleValue::new("west").alias("w"),
+            Direction::NORTHWEST => PossibleValue::new("nordwest").alias("nw"),
+        })
+    }
+}
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::NORTH => write!(f, "Nord"),
+            Direction::NORTHEAST => write!(f, "Nordost"),
+            Direction::EAST => write!(f, "Ost"),
+            Direction::SOUTHEAST => write!(f, "Südost"),
+            Direction::SOUTH => write!(f, "Süd"),
+            Direction::SOUTHWEST => write!(f, "Südwest"),
+            Direction::WEST => write!(f, "West"),
+            Direction::NORTHWEST => write!(f, "Nordwest"),
+        }
+    }
+}
+
+/// Der Seegang, wie er sich aus der Windstärke ergibt
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
+pub enum SeaState {
+    CALM,
+    SMOOTH,
+    SLIGHT,
+    MODERATE,
+    ROUGH,
+    VERY_ROUGH,
+    HIGH
+}
+impl SeaState {
+    pub fn from_wind(wind: &Wind) -> SeaState {
+        match wind {
+            Wind::NONE => SeaState::CALM,
+            Wind::LIGHT => SeaState::SMOOTH,
+            Wind::SOFT => SeaState::SLIGHT,
+            Wind::FRESH => SeaState::MODERATE,
+            Wind::COOL => SeaState::ROUGH,
+            Wind::STRONG => SeaState::VERY_ROUGH,
+            Wind::STORM | Wind::SANDSTORM => SeaState::HIGH,
+        }
+    }
+}
+impl std::fmt::Display for SeaState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SeaState::CALM => write!(f, "spiegelglatte See"),
+            SeaState::SMOOTH => write!(f, "ruhige See"),
+            SeaState::SLIGHT => write!(f, "leicht bewegte See"),
+            SeaState::MODERATE => write!(f, "mäßig bewegte See"),
+            SeaState::ROUGH => write!(f, "grobe See"),
+            SeaState::VERY_ROUGH => write!(f, "sehr grobe See"),
+            SeaState::HIGH => write!(f, "hohe See"),
+        }
+    }
+}
+
+/// Wie der Wind zum Kurs des Schiffs steht
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PointOfSail {
+    AGAINST,
+    CLOSE,
+    BEAM,
+    BROAD,
+    RUNNING
+}
+impl PointOfSail {
+    /// Aus dem Winkel zwischen Kurs und Windrichtung in Achteln
+    pub fn from_eighths(eighths: u32) -> PointOfSail {
+        match eighths {
+            0 => PointOfSail::AGAINST,
+            1 => PointOfSail::CLOSE,
+            2 => PointOfSail::BEAM,
+            3 => PointOfSail::BROAD,
+            _ => PointOfSail::RUNNING,
+        }
+    }
+    /// Gegen den Wind und hart am Wind muss gekreuzt werden
+    pub fn needs_tacking(&self) -> bool {
+        matches!(self, PointOfSail::AGAINST | PointOfSail::CLOSE)
+    }
+    fn speed(&self) -> f32 {
+        match self {
+            PointOfSail::AGAINST | PointOfSail::CLOSE => 0.5,
+            PointOfSail::BEAM => 1.0,
+            PointOfSail::BROAD => 1.1,
+            PointOfSail::RUNNING => 0.9,
+        }
+    }
+}
+impl std::fmt::Display for PointOfSail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PointOfSail::AGAINST => write!(f, "gegen den Wind"),
+            PointOfSail::CLOSE => write!(f, "hart am Wind"),
+            PointOfSail::BEAM => write!(f, "halber Wind"),
+            PointOfSail::BROAD => write!(f, "raumer Wind"),
+            PointOfSail::RUNNING => write!(f, "vor dem Wind"),
+        }
+    }
+}
+
+/// Ein Kurs und was der Wind des Tages für ihn bedeutet. Bei Sturm wird beigedreht, die Fahrt ist dann 0.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Course {
+    pub heading: Direction,
+    /// Bei Flaute keine
+    pub point: Option<PointOfSail>,
+    pub tacking: bool,
+    /// Fahrt unter Segeln in Prozent der üblichen Reisegeschwindigkeit
+    pub speed: u32
+}
+impl Course {
+    pub fn new(heading: Direction, wind: &Wind, from: &Direction) -> Course {
+        let point = PointOfSail::from_eighths(heading.eighths_to(from));
+        let strength = match wind {
+            Wind::NONE | Wind::STORM | Wind::SANDSTORM => 0.0,
+            Wind::LIGHT => 0.5,
+            Wind::SOFT => 0.75,
+            Wind::FRESH | Wind::STRONG => 1.0,
+            Wind::COOL => 1.25,
+        };
+        match wind {
+            Wind::NONE => Course { heading, point: None, tacking: false, speed: 0 },
+            _ => {
+                let speed = (100.0 * strength * point.speed()) as u32;
+                Course { heading, point: Some(point), tacking: speed > 0 && point.needs_tacking(), speed }
+            },
+        }
+    }
+}
+impl std::fmt::Display for Course {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.point {
+            None => write!(f, "Kurs {}: Flaute", self.heading)?,
+            Some(_) if self.speed == 0 => write!(f, "Kurs {}: beidrehen", self.heading)?,
+            Some(p) => write!(f, "Kurs {}: {}", self.heading, p)?,
+        }
+        if self.tacking {write!(f, ", kreuzen")?;}
+        write!(f, ", Fahrt {} %", self.speed)
+    }
+}
+
+/// Das Wetter auf See
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Sea {
+    pub state: SeaState,
+    pub course: Option<Course>
+}
+impl std::fmt::Display for Sea {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.state)?;
+        if let Some(c) = &self.course {write!(f, ", {}", c)?;}
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Rain {
+    NONE,
+    LITTLE,
+    LOTS,
+    ALL
+}
+impl std::fmt::Display for Rain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rain::NONE => write!(f, "kein Niederschlag"),
+            Rain::LITTLE => write!(f, "leichter Niederschlag"),
+            Rain::LOTS => write!(f, "kräftiger Niederschlag"),
+            Rain::ALL => write!(f, "Wolkenbruch"),
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Precipitation {
+    RAIN,
+    SNOW,
+    SLEET,
+    HAIL
+}
+impl Precipitation {
+    /// Die Art des Niederschlags ergibt sich aus den Temperaturen des Tages.
+    /// Hagel gibt es nur als gewürfeltes Ereignis bei kräftigem Regen, siehe `Phenomenon::HAIL`.
+    pub fn from_weather(rain: &Rain, day_temp: i32, night_temp: i32) -> Option<Precipitation> {
+        match rain {
+            Rain::NONE => None,
+            _ if day_temp <= 0 => Some(Precipitation::SNOW),
+            _ if night_temp <= 0 => Some(Precipitation::SLEET),
+            _ => Some(Precipitation::RAIN),
+        }
+    }
+}
+impl std::fmt::Display for Precipitation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Precipitation::RAIN => write!(f, "Regen"),
+            Precipitation::SNOW => write!(f, "Schnee"),
+            Precipitation::SLEET => write!(f, "Schneeregen"),
+            Precipitation::HAIL => write!(f, "Hagel"),
+        }
+    }
+}
+/// Besondere Wetterereignisse, die sich aus dem Wetter eines Tages ergeben können
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Phenomenon {
+    THUNDERSTORM,
+    FOG,
+    HAIL,
+    BLIZZARD,
+    HEATWAVE,
+    FROST
+}
+impl Phenomenon {
+    pub const ALL: [Phenomenon; 6] = [Phenomenon::THUNDERSTORM, Phenomenon::FOG, Phenomenon::HAIL, Phenomenon::BLIZZARD, Phenomenon::HEATWAVE, Phenomenon::FROST];
+
+    /// Die Chance auf einem W20, 0 wenn das Wetter des Tages nicht passt. Hagel ist bei einem Gewitter am gleichen Tag wahrscheinlicher.
+    pub fn chance(&self, day: &Day) -> u32 {
+        let wet_region = matches!(day.region, Region::THORWAL | Region::WEIDEN | Region::ECHSENSUEMPFE | Region::SUEDMEER);
+        match self {
+            Phenomenon::THUNDERSTORM if day.clouds == Clouds::ALL && matches!(day.rain, Rain::LOTS | Rain::ALL)
+                && day.precipitation == Some(Precipitation::RAIN) && day.day_temp >= 20 => match day.season {
+                Season::SUMMER | Season::SPRING if matches!(day.region, Region::ECHSENSUEMPFE | Region::SUEDMEER) => 12,
+                Season::SUMMER | Season::SPRING => 8,
+                _ => 2,
+            },
+            Phenomenon::FOG if !day.desert && matches!(day.wind, Wind::NONE | Wind::LIGHT) && day.rain == Rain::NONE => {
+                let chance = match day.season {
+                    Season::AUTUMN | Season::SPRING => 6,
+                    Season::WINTER => 3,
+                    Season::SUMMER => 2,
+                };
+                if wet_region {chance + 4} else {chance}
+            },
+            Phenomenon::HAIL if day.precipitation == Some(Precipitation::RAIN) && matches!(day.rain, Rain::LOTS | Rain::ALL) && day.day_temp >= 15 => {
+                let thunderstorm = day.events.iter().any(|e| e.kind == Phenomenon::THUNDERSTORM);
+                match day.rain {
+                    Rain::ALL if thunderstorm => 6,
+                    _ if thunderstorm => 4,
+                    Rain::ALL => 2,
+                    _ => 1,
+                }
+            },
+            Phenomenon::BLIZZARD if day.precipitation == Some(Precipitation::SNOW) && matches!(day.wind, Wind::STRONG | Wind::STORM) => 20,
+            Phenomenon::HEATWAVE if day.season == Season::SUMMER && day.day_temp >= 35 && matches!(day.clouds, Clouds::NONE | Clouds::FEW)
+                && matches!(day.wind, Wind::NONE | Wind::LIGHT | Wind::SOFT) => 10,
+            Phenomenon::FROST if matches!(day.season, Season::SPRING | Season::AUTUMN) && day.night_temp <= 0 && day.day_temp > 0 => 12,
+            _ => 0,
+        }
+    }
+    /// Wie lange das Ereignis anhält
+    pub fn duration(&self) -> DiceOverTime {
+        match self {
+            Phenomenon::THUNDERSTORM => DiceOverTime { dice: 1, flat: 0, time: Timeunit::STD },
+            Phenomenon::FOG => DiceOverTime { dice: 1, flat: 2, time: Timeunit::STD },
+            Phenomenon::HAIL => DiceOverTime { dice: 2, flat: 0, time: Timeunit::SR },
+            Phenomenon::BLIZZARD => DiceOverTime { dice: 2, flat: 0, time: Timeunit::STD },
+            Phenomenon::HEATWAVE => DiceOverTime { dice: 0, flat: 12, time: Timeunit::STD },
+            Phenomenon::FROST => DiceOverTime { dice: 1, flat: 6, time: Timeunit::STD },
+        }
+    }
+    /// Was das Ereignis im Spiel bedeutet
+    pub fn effects(&self) -> Vec<&'static str> {
+        match self {
+            Phenomenon::THUNDERSTORM => vec!["Sinnesschärfe (Hören) +3", "Blitzschlag auf freiem Feld und in Metallrüstung möglich", "Reittiere scheuen, Reiten +3"],
+            Phenomenon::FOG => vec!["Sicht unter 20 Schritt", "Orientierung +5", "Fernkampf nur auf nahe Distanz"],
+            Phenomenon::HAIL => vec!["1 SP je SR ohne Deckung", "Zelte und Ernte werden beschädigt"],
+            Phenomenon::BLIZZARD => vec!["Sicht unter 5 Schritt", "Orientierung +7", "Reisen kaum möglich", "Kälteschaden doppelt"],
+            Phenomenon::HEATWAVE => vec!["Doppelter Wasserbedarf", "Hitze eine Stufe höher"],
+            Phenomenon::FROST => vec!["Reif und Glätte am Morgen", "Gefrorener Boden, Fährtensuchen +2"],
+        }
+    }
+}
+impl std::fmt::Display for Phenomenon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phenomenon::THUNDERSTORM => write!(f, "Gewitter"),
+            Phenomenon::FOG => write!(f, "Nebel"),
+            Phenomenon::HAIL => write!(f, "Hagel"),
+            Phenomenon::BLIZZARD => write!(f, "Schneesturm"),
+            Phenomenon::HEATWAVE => write!(f, "Hitzewelle"),
+            Phenomenon::FROST => write!(f, "Frostnacht"),
+        }
+    }
+}
+/// Ein eingetretenes Wetterereignis mit gewürfelter Dauer
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeatherEvent {
+    pub kind: Phenomenon,
+    pub duration: u32,
+    pub time: Timeunit,
+    #[serde(default)]
+    pub effects: Vec<String>
+}
+impl WeatherEvent {
+    pub fn new(kind: Phenomenon, rng: &mut impl Rng) -> Result<Self> {
+        let duration = kind.duration();
+        Ok(WeatherEvent { kind, duration: duration.roll(rng)?.total.max(0) as u32, time: duration.time, effects: kind.effects().iter().map(|e| e.to_string()).collect() })
+    }
+}
+impl std::fmt::Display for WeatherEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} für {} {}", self.kind, self.duration, self.time)?;
+        for e in &self.effects {write!(f, " ({})", e)?;}
+        Ok(())
+    }
+}
+
+/// Was das Wetter eines Tages für die Regeln bedeutet. Positive Werte erschweren die Probe, negative erleichtern sie.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Modifiers {
+    pub orientation: i32,
+    pub survival: i32,
+    pub tracking: i32,
+    pub ranged: i32,
+    /// Reisegeschwindigkeit in Prozent der üblichen
+    pub travel: u32,
+    /// Kältestufe der Nacht, je 10 °C unter 0 eine Stufe
+    pub cold: u32,
+    /// Hitzestufe des Tages, je 5 °C ab 30 °C eine Stufe
+    pub heat: u32
+}
+impl Default for Modifiers {
+    fn default() -> Self {
+        Modifiers { orientation: 0, survival: 0, tracking: 0, ranged: 0, travel: 100, cold: 0, heat: 0 }
+    }
+}
+impl Modifiers {
+    pub fn from_day(day: &Day) -> Modifiers {
+        let has = |p: Phenomenon| day.events.iter().any(|e| e.kind == p);
+        let mut m = Modifiers::default();
+        let mut travel = 1.0;
+
+        if day.clouds == Clouds::ALL {m.orientation += 2;}
+        match day.rain {
+            Rain::NONE => (),
+            Rain::LITTLE => {m.survival += 1; m.tracking += 1;},
+            Rain::LOTS => {m.orientation += 1; m.survival += 2; m.tracking += 2; m.ranged += 1; travel *= 0.75;},
+            Rain::ALL => {m.orientation += 2; m.survival += 4; m.tracking += 4; m.ranged += 2; travel *= 0.5;},
+        }
+        match day.precipitation {
+            Some(Precipitation::SNOW) if day.rain == Rain::LITTLE => m.tracking -= 3,
+            Some(Precipitation::SNOW) | Some(Precipitation::SLEET) => travel *= 0.75,
+            _ => (),
+        }
+        match day.wind {
+            Wind::FRESH => m.ranged += 1,
+            Wind::COOL => m.ranged += 2,
+            Wind::STRONG => {m.survival += 2; m.tracking += 1; m.ranged += 4; travel *= 0.75;},
+            Wind::STORM => {m.survival += 4; m.tracking += 2; m.ranged += 8; travel *= 0.5;},
+            Wind::SANDSTORM => {m.orientation += 7; m.survival += 4; m.tracking += 4; m.ranged += 8; travel *= 0.25;},
+            _ => (),
+        }
+        if has(Phenomenon::FOG) {m.orientation += 5; m.ranged += 4; travel *= 0.75;}
+        if has(Phenomenon::BLIZZARD) {m.orientation += 7; m.survival += 4; m.ranged += 8; travel *= 0.25;}
+        if has(Phenomenon::HEATWAVE) {m.survival += 2; travel *= 0.75;}
+        if has(Phenomenon::FROST) {m.tracking += 2;}
+
+        m.travel = (100.0 * travel) as u32;
+        m.cold = if day.night_temp < 0 {(9 - day.night_temp) as u32 / 10} else {0};
+        m.heat = if day.day_temp >= 30 {(day.day_temp - 25) as u32 / 5} else {0};
+        if has(Phenomenon::HEATWAVE) {m.heat += 1;}
+        m.survival += (m.cold + m.heat) as i32;
+        m
+    }
+    /// Die Proben mit Zuschlag oder Erleichterung, z.B. "Orientierung +2"
+    fn checks(&self) -> Vec<String> {
+        [("Orientierung", self.orientation), ("Wildnisleben", self.survival), ("Fährtensuchen", self.tracking), ("Fernkampf", self.ranged)]
+            .iter().filter(|(_, v)| *v != 0).map(|(n, v)| format!("{} {:+}", n, v)).collect()
+    }
+    pub fn cold_str(&self) -> Option<String> {
+        match self.cold {
+            0 => None,
+            c => Some(format!("Kälte Stufe {}: {}W6 SP in der Nacht ohne warme Kleidung, Zelt oder Feuer", c, c)),
+        }
+    }
+    pub fn heat_str(&self) -> Option<String> {
+        match self.heat {
+            0 => None,
+            1 => Some("Hitze Stufe 1: bei Anstrengung alle 4 Stunden KO-Probe +1, sonst 1W6 Erschöpfung".to_string()),
+            h if h < 4 => Some(format!("Hitze Stufe {}: bei Anstrengung alle {} Stunden KO-Probe +{}, sonst 1W6 Erschöpfung", h, 5 - h, h)),
+            h => Some(format!("Hitze Stufe {}: bei Anstrengung jede Stunde KO-Probe +{}, sonst 1W6 Erschöpfung", h, h)),
+        }
+    }
+    pub fn any(&self) -> bool {
+        *self != Modifiers::default()
+    }
+    /// Proben und Reisegeschwindigkeit, mit `exposure` auch Kälte- und Hitzestufe
+    fn parts(&self, exposure: bool) -> Vec<String> {
+        let mut parts = self.checks();
+        if self.travel != 100 {parts.push(format!("Reisegeschwindigkeit {} %", self.travel));}
+        if !exposure {
+            return parts;
+        }
+        if self.cold > 0 {parts.push(format!("Kälte Stufe {}", self.cold));}
+        if self.heat > 0 {parts.push(format!("Hitze Stufe {}", self.heat));}
+        parts
+    }
+}
+impl std::fmt::Display for Modifiers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.parts(true).as_slice() {
+            [] => write!(f, "keine Auswirkungen auf Proben"),
+            parts => write!(f, "{}", parts.join(", ")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Day {
+    pub no: u64,
+    pub region: Region,
+    #[serde(default)]
+    pub season: Season,
+    pub clouds: Clouds,
+    pub wind: Wind,
+    /// Woher der Wind weht, nur mit `sea`. Sie wandert von Tag zu Tag ein wenig weiter.
+    #[serde(default)]
+    pub wind_direction: Option<Direction>,
+    pub day_temp: i32,
+    pub night_temp: i32,
+    pub rain: Rain,
+    pub precipitation: Option<Precipitation>,
+    pub date: Option<AvDate>,
+    pub weekday: Option<Weekday>,
+    /// Ob der Tag in der Wüste liegt, mit eigenen Tabellen für Wolken, Temperatur, Wind und Niederschlag
+    #[serde(default)]
+    pub desert: bool,
+    #[serde(default)]
+    pub events: Vec<WeatherEvent>,
+    /// Die Auswirkungen auf Proben, Reise und Gesundheit
+    #[serde(default)]
+    pub modifiers: Modifiers,
+    /// Seegang und Kurs, nur mit `sea`
+    #[serde(default)]
+    pub sea: Option<Sea>,
+    /// Was sich gegenüber dem Vortag geändert hat. Am ersten Tag nichts.
+    #[serde(default)]
+    pub changes: Changes
+}
+impl Day {
+    pub fn date_str(&self) -> String {
+        match (&self.date, &self.weekday) {
+            (Some(d), Some(w)) => format!("{}, {}", w, d),
+            (Some(d), None) => d.to_string(),
+            _ => String::new(),
+        }
+    }
+    fn title(&self) -> String {
+        match &self.date {
+            Some(_) => format!("Tag {} ({}, {})", self.no, self.date_str(), self.region_str()),
+            None => format!("Tag {} ({})", self.no, self.region_str()),
+        }
+    }
+    fn region_str(&self) -> String {
+        if self.desert {format!("{}, Wüste", self.region)} else {self.region.to_string()}
+    }
+    pub fn rain_str(&self) -> String {
+        match &self.precipitation {
+            Some(p) => format!("{} ({})", self.rain, p),
+            None => format!("{}", self.rain),
+        }
+    }
+    pub fn wind_str(&self) -> String {
+        match (self.wind, self.wind_direction) {
+            (Wind::NONE, _) | (_, None) => self.wind.to_string(),
+            (_, Some(d)) => format!("{} aus {}", self.wind, d),
+        }
+    }
+    fn sea_str(&self) -> String {
+        match &self.sea {
+            Some(sea) => format!(", {}", sea),
+            None => String::new(),
+        }
+    }
+    fn events_str(&self) -> String {
+        self.events.iter().map(|e| format!(", {}", e)).collect()
+    }
+    fn modifiers_str(&self) -> String {
+        if self.modifiers.any() {format!("; {}", self.modifiers)} else {String::new()}
+    }
+    fn changes_str(&self) -> String {
+        if self.changes.any() {format!(" ({})", self.changes)} else {String::new()}
+    }
+}
+impl CsvRecord for Day {
+    fn header(records: &[Self]) -> Vec<String> {
+        let mut header: Vec<String> = ["Tag", "Datum", "Wochentag", "Region", "Bewölkung", "Wind", "Tagestemperatur", "Nachttemperatur", "Niederschlag", "Art", "Wüste", "Ereignisse", "Auswirkungen",
+            "Orientierung", "Wildnisleben", "Fährtensuchen", "Fernkampf", "Reisegeschwindigkeit", "Kälte", "Hitze",
+            "Bewölkung geändert", "Wind geändert", "Temperatur geändert", "Niederschlag geändert"]
+            .iter().map(|h| h.to_string()).collect();
+        if records.iter().any(|d| d.sea.is_some()) {
+            header.extend(["Windrichtung", "Seegang", "Kurs", "Segelstellung", "Kreuzen", "Fahrt"].iter().map(|h| h.to_string()));
+        }
+        header
+    }
+    fn fields(&self) -> Vec<String> {
+        let opt = |o: Option<String>| o.unwrap_or_default();
+        let yes = |b: bool| if b {"ja".to_string()} else {"nein".to_string()};
+        let mut fields = vec![self.no.to_string(), opt(self.date.map(|d| d.to_string())), opt(self.weekday.map(|w| w.to_string())), self.region.to_string(),
+            self.clouds.to_string(), self.wind.to_string(), self.day_temp.to_string(), self.night_temp.to_string(), self.rain.to_string(),
+            opt(self.precipitation.as_ref().map(|p| p.to_string())), yes(self.desert),
+            self.events.iter().map(|e| format!("{} für {} {}", e.kind, e.duration, e.time)).collect::<Vec<_>>().join("; "),
+            self.events.iter().flat_map(|e| e.effects.clone()).collect::<Vec<_>>().join("; "),
+            self.modifiers.orientation.to_string(), self.modifiers.survival.to_string(), self.modifiers.tracking.to_string(), self.modifiers.ranged.to_string(),
+            self.modifiers.travel.to_string(), self.modifiers.cold.to_string(), self.modifiers.heat.to_string(),
+            yes(self.changes.clouds), yes(self.changes.wind), yes(self.changes.temperature), yes(self.changes.rain)];
+        if let Some(sea) = &self.sea {
+            fields.push(opt(self.wind_direction.map(|d| d.to_string())));
+            fields.push(sea.state.to_string());
+            match &sea.course {
+                Some(c) => fields.extend([c.heading.to_string(), opt(c.point.map(|p| p.to_string())), yes(c.tacking), c.speed.to_string()]),
+                None => fields.extend(vec![String::new(); 4]),
+            }
+        }
+        fields
+    }
+}
+impl Render for Day {
+    fn text(&self) -> String {
+        self.to_string()
+    }
+    fn md(&self) -> String {
+        format!("- {}: {}, {}, {} - {}, {}{}{}{}{}", self.title(), self.clouds, self.wind_str(), self.day_temp, self.night_temp, self.rain_str(), self.sea_str(), self.events_str(), self.modifiers_str(), self.changes_str())
+    }
+}
+impl Labels for Day {
+    fn labels(&self) -> serde_json::Value {
+        serde_json::json!({
+            "title": self.title(),
+            "date": self.date.map(|d| d.to_string()),
+            "weekday": self.weekday.map(|w| w.to_string()),
+            "region": self.region.to_string(),
+            "season": self.season.to_string(),
+            "desert": if self.desert {Some("Wüste")} else {None},
+            "clouds": self.clouds.to_string(),
+            "wind": self.wind_str(),
+            "wind_direction": self.wind_direction.map(|d| d.to_string()),
+            "day_temp": format!("{} °C", self.day_temp),
+            "night_temp": format!("{} °C", self.night_temp),
+            "rain": self.rain_str(),
+            "precipitation": self.precipitation.as_ref().map(|p| p.to_string()),
+            "events": self.events.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+            "modifiers": self.modifiers.to_string(),
+            "sea": self.sea.as_ref().map(|s| s.to_string()),
+            "changes": self.changes.to_string(),
+        })
+    }
+}
+impl std::fmt::Display for Day {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\n{} {}\nTemperatur von {} bis {}\n{}", self.title(), self.clouds, self.wind_str(), self.day_temp, self.night_temp, self.rain_str())?;
+        if let Some(sea) = &self.sea {write!(f, "\n{}", sea)?;}
+        for e in &self.events {write!(f, "\n{}", e)?;}
+        let parts = self.modifiers.parts(false);
+        if !parts.is_empty() {write!(f, "\n{}", parts.join(", "))?;}
+        for s in self.modifiers.cold_str().iter().chain(self.modifiers.heat_str().iter()) {write!(f, "\n{}", s)?;}
+        if self.changes.any() {write!(f, "\n{}", self.changes)?;}
+        Ok(())
+    }
+}
+
+/// Welche Teile des Wetters sich über Nacht ändern: als Wurf nach WdE, S.158 für den nächsten Tag und am Tag selbst als tatsächliche Änderung
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "ChangesRepr")]
+pub struct Changes {
+    pub clouds: bool,
+    pub wind: bool,
+    pub temperature: bool,
+    pub rain: bool
+}
+impl Changes {
+    pub const NONE: Changes = Changes { clouds: false, wind: false, temperature: false, rain: false };
+    pub const ALL: Changes = Changes { clouds: true, wind: true, temperature: true, rain: true };
+    pub const CLOUDS: Changes = Changes { clouds: true, ..Changes::NONE };
+    pub const WIND: Changes = Changes { wind: true, ..Changes::NONE };
+    pub const TEMPERATURE: Changes = Changes { temperature: true, ..Changes::NONE };
+    pub const RAIN: Changes = Changes { rain: true, ..Changes::NONE };
+
+    pub fn any(&self) -> bool {
+        self.clouds || self.wind || self.temperature || self.rain
+    }
+    /// Was sich von einem Tag zum nächsten tatsächlich geändert hat
+    pub fn between(before: &Day, after: &Day) -> Changes {
+        Changes {
+            clouds: before.clouds != after.clouds,
+            wind: before.wind != after.wind,
+            temperature: before.day_temp != after.day_temp || before.night_temp != after.night_temp,
+            rain: before.rain != after.rain || before.precipitation != after.precipitation,
+        }
+    }
+    fn names(&self) -> Vec<&'static str> {
+        [(self.clouds, "Bewölkung"), (self.wind, "Wind"), (self.temperature, "Temperatur"), (self.rain, "Niederschlag")]
+            .iter().filter(|(c, _)| *c).map(|(_, n)| *n).collect()
+    }
+}
+impl std::ops::BitOr for Changes {
+    type Output = Changes;
+
+    fn bitor(self, rhs: Changes) -> Changes {
+        Changes { clouds: self.clouds || rhs.clouds, wind: self.wind || rhs.wind, temperature: self.temperature || rhs.temperature, rain: self.rain || rhs.rain }
+    }
+}
+impl std::ops::BitOrAssign for Changes {
+    fn bitor_assign(&mut self, rhs: Changes) {
+        *self = *self | rhs;
+    }
+}
+impl std::ops::Sub for Changes {
+    type Output = Changes;
+
+    fn sub(self, rhs: Changes) -> Changes {
+        Changes { clouds: self.clouds && !rhs.clouds, wind: self.wind && !rhs.wind, temperature: self.temperature && !rhs.temperature, rain: self.rain && !rhs.rain }
+    }
+}
+impl std::fmt::Display for Changes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.names().as_slice() {
+            [] => write!(f, "keine Änderung"),
+            names => write!(f, "Änderung bei {}", names.join(", ")),
+        }
+    }
+}
+/// Ältere Zustandsdateien speichern die Änderungen noch als Bitmaske
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChangesRepr {
+    Bits(usize),
+    Fields { clouds: bool, wind: bool, temperature: bool, rain: bool }
+}
+impl From<ChangesRepr> for Changes {
+    fn from(repr: ChangesRepr) -> Self {
+        match repr {
+            ChangesRepr::Bits(b) => Changes { clouds: b & 0b0001 > 0, wind: b & 0b0010 > 0, temperature: b & 0b0100 > 0, rain: b & 0b1000 > 0 },
+            ChangesRepr::Fields { clouds, wind, temperature, rain } => Changes { clouds, wind, temperature, rain },
+        }
+    }
+}
+
+/// Die Eingaben für das Wetter nach WdE, S.156ff.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WeatherParams {
+    pub region: Region,
+    pub season: Season,
+    pub desert: bool,
+    pub windy: bool,
+    /// Mit einem Startdatum wird jeder Tag datiert und die Jahreszeit ergibt sich aus dem Monat
+    pub start: Option<AvDate>,
+    /// Auf See gibt es zu jedem Tag Seegang und, mit `heading`, die Fahrt auf diesem Kurs
+    #[serde(default)]
+    pub sea: bool,
+    #[serde(default)]
+    pub heading: Option<Direction>
+}
+impl Default for WeatherParams {
+    fn default() -> Self {
+        WeatherParams { region: Region::MITTELREICH, season: Season::SUMMER, desert: false, windy: false, start: None, sea: false, heading: None }
+    }
+}
+
+/// Ein Abschnitt einer Reise: so viele Tage in einer Region
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Leg {
+    pub region: Region,
+    pub days: u64,
+    #[serde(default)]
+    pub desert: bool
+}
+impl std::str::FromStr for Leg {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Regions::default().leg(s)
+    }
+}
+
+/// Der Zustand nach dem letzten generierten Tag, um das Wetter in einem späteren Lauf fortzusetzen
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WeatherState {
+    pub params: WeatherParams,
+    pub last: Day,
+    #[serde(alias = "flags")]
+    pub changes: Changes,
+    pub seed: u64
+}
+impl WeatherState {
+    pub fn load(path: &std::path::Path) -> Result<WeatherState> {
+        read_json(path)
+    }
+    pub fn save(&self, path: &std::path::Path) -> Result<()> {
+        write_json(path, self)
+    }
+}
+
+/// Erzeugt fortlaufend Tage, ausgehend vom übergebenen Zufallsgenerator.
+pub struct WeatherGenerator<R: Rng> {
+    params: WeatherParams,
+    rng: R,
+    last: Option<Day>,
+    changes: Changes
+}
+impl<R: Rng> WeatherGenerator<R> {
+    pub fn new(params: WeatherParams, rng: R) -> Self {
+        WeatherGenerator { params, rng, last: None, changes: Changes::ALL }
+    }
+    /// Setzt das Wetter nach dem Tag im Zustand fort. Der Zufallsgenerator sollte mit `state.seed` erzeugt sein.
+    pub fn from_state(state: WeatherState, rng: R) -> Self {
+        WeatherGenerator { params: state.params, rng, last: Some(state.last), changes: state.changes }
+    }
+    /// Der Zustand für die Fortsetzung. Der Seed für den nächsten Lauf wird dabei aus dem Zufallsgenerator gezogen.
+    pub fn state(&mut self) -> Option<WeatherState> {
+        let last = self.last.clone()?;
+        Some(WeatherState { params: self.params.clone(), last, changes: self.changes, seed: self.rng.gen() })
+    }
+    pub fn params(&self) -> &WeatherParams {
+        &self.params
+    }
+    /// Wechselt die Region ab dem nächsten Tag. Die Temperatur wird dann neu bestimmt, Wolken und Wind folgen weiter dem Wetterwechsel.
+    pub fn set_region(&mut self, region: Region, desert: bool) {
+        if region != self.params.region || desert != self.params.desert {
+            self.changes |= Changes::TEMPERATURE;
+        }
+        self.params.region = region;
+        self.params.desert = desert;
+    }
+    /// Erzeugt das Wetter einer Reise als durchgehende Folge von Tagen über alle Abschnitte
+    pub fn travel(&mut self, route: &[Leg]) -> Result<Vec<Day>> {
+        let mut days = Vec::new();
+        for leg in route {
+            self.set_region(leg.region.clone(), leg.desert);
+            for _ in 0..leg.days {
+                days.push(self.next_day()?);
+            }
+        }
+        Ok(days)
+    }
+    pub fn next_day(&mut self) -> Result<Day> {
+        let mut changes = self.changes;
+        let date = match &self.last {
+            Some(d) => d.date.map(|d| d.next()),
+            None => self.params.start,
+        };
+        if let Some(date) = &date {
+            if date.season() != self.params.season {
+                self.params.season = date.season();
+                changes |= Changes::TEMPERATURE;
+            }
+        }
+        let desert = self.params.desert || self.params.region.is_desert();
+        let rng = &mut self.rng;
+        let last = &self.last;
+        let res1 = if changes.clouds {step1(&self.params, rng)?} else { match last {
+            Some(d) => d.clouds,
+            None => return Err(Error::NoPreviousDay),
+        } };
+        let res2 = if changes.wind {step2(&self.params, rng)?} else { match last {
+            Some(d) => d.wind,
+            None => return Err(Error::NoPreviousDay),
+        } }.in_desert(desert);
+        let direction = if self.params.sea {Some(drift(&self.params, rng, last.as_ref().and_then(|d| d.wind_direction), changes.wind))} else {None};
+        let res3 = if changes.temperature {step3(&self.params, rng, res1.temp_mod(), res2.temp_mod())} else { match last {
+            Some(d) => (d.day_temp, d.night_temp),
+            None => return Err(Error::NoPreviousDay),
+        } };
+        let res4 = if changes.rain {step4(rng, &res1, &res2, desert)?} else { match last {
+            Some(d) => d.rain,
+            None => return Err(Error::NoPreviousDay),
+        } };
+        let no = match last {
+            Some(d) => d.no + 1,
+            None => 1,
+        };
+
+        let precipitation = Precipitation::from_weather(&res4, res3.0, res3.1);
+        let mut day = Day { no, region: self.params.region.clone(), season: self.params.season.clone(), clouds: res1, wind: res2, wind_direction: direction, day_temp: res3.0, night_temp: res3.1,
+            rain: res4, precipitation, date, weekday: date.map(|d| d.weekday()), desert, events: Vec::new(), modifiers: Modifiers::default(), sea: None, changes: Changes::NONE };
+        step5(&mut day, rng)?;
+        day.modifiers = Modifiers::from_day(&day);
+        if let Some(direction) = &day.wind_direction {
+            let course = self.params.heading.map(|h| Course::new(h, &day.wind, direction));
+            day.sea = Some(Sea { state: SeaState::from_wind(&day.wind), course });
+        }
+        if let Some(last) = last {
+            day.changes = Changes::between(last, &day);
+        }
+        self.changes = step6(&self.params, &mut self.rng)?;
+        self.last = Some(day.clone());
+        Ok(day)
+    }
+}
+impl<R: Rng> Iterator for WeatherGenerator<R> {
+    type Item = Result<Day>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_day())
+    }
+}
+
+fn step1(params: &WeatherParams, rng: &mut impl Rng) -> Result<Clouds> {
+    let roll = rng.gen_range(1..=20);
+    if let Region::CUSTOM(c) = &params.region {
+        if let Some(clouds) = lookup(&c.clouds, roll) {
+            return Ok(clouds);
+        }
+    }
+    if params.desert || params.region.is_desert() {
+        return match roll {
+            1..=16 => Ok(Clouds::NONE),
+            17..=18 => Ok(Clouds::FEW),
+            19 => Ok(Clouds::LOTS),
+            20 => Ok(Clouds::ALL),
+            _ => Err(Error::Roll("Bewölkung", roll))
+        };
+    }
+    match roll {
+        1..=4 => Ok(Clouds::NONE),
+        5..=10 => Ok(Clouds::FEW),
+        11..=16 => Ok(Clouds::LOTS),
+        17..=20 => Ok(Clouds::ALL),
+        _ => Err(Error::Roll("Bewölkung", roll))
+    }
+}
+fn step2(params: &WeatherParams, rng: &mut impl Rng) -> Result<Wind> {
+    let roll = if params.windy || params.region.is_windy() {rng.gen_range(1..=20) + 2} else {rng.gen_range(1..=20)};
+    if let Region::CUSTOM(c) = &params.region {
+        if let Some(wind) = lookup(&c.wind, roll) {
+            return Ok(wind);
+        }
+    }
+
+    let wind = if params.season == Season::AUTUMN {
+        match roll {
+            1..=3 => Wind::NONE,
+            4..=5 => Wind::LIGHT,
+            6..=7 => Wind::SOFT,
+            8..=10 => Wind::FRESH,
+            11..=14 => Wind::COOL,
+            15..=18 => Wind::STRONG,
+            19..=22 => Wind::STORM,
+            _ => return Err(Error::Roll("Wind", roll))
+        }
+    } else {
+        match roll {
+            1..=4 => Wind::NONE,
+            5..=7 => Wind::LIGHT,
+            8..=10 => Wind::SOFT,
+            11..=13 => Wind::FRESH,
+            14..=16 => Wind::COOL,
+            17..=19 => Wind::STRONG,
+            20..=22 => Wind::STORM,
+            _ => return Err(Error::Roll("Wind", roll))
+        }
+    };
+    Ok(wind)
+}
+/// Die Windrichtung wandert jeden Tag ein wenig, bei einem Wechsel des Windes auch weiter.
+/// Am ersten Tag gilt die vorherrschende Richtung der Region, sonst wird sie ausgewürfelt.
+fn drift(params: &WeatherParams, rng: &mut impl Rng, last: Option<Direction>, changed: bool) -> Direction {
+    let from = match last.or(params.region.prevailing_wind()) {
+        Some(d) => d,
+        None => return Direction::ALL[rng.gen_range(0..8)],
+    };
+    let roll = rng.gen_range(1..=20);
+    let turn = if changed {
+        match roll {
+            1..=2 => -2,
+            3..=6 => -1,
+            15..=18 => 1,
+            19..=20 => 2,
+            _ => 0
+        }
+    } else {
+        match roll {
+            1..=3 => -1,
+            18..=20 => 1,
+            _ => 0
+        }
+    };
+    from.turn(turn)
+}
+/// In der Wüste kühlt es nachts um weitere 10 °C ab
+fn step3(params: &WeatherParams, rng: &mut impl Rng, clouds_mod: i32, wind_mod: i32) -> (i32, i32) {
+    let roll = rng.gen_range(1..=20)+5;
+    let roll = if params.desert || params.region.is_desert() {roll + 10} else {roll};
+    (
+        params.region.temp_base(&params.season) + wind_mod + clouds_mod,
+        params.region.temp_base(&params.season) + wind_mod - clouds_mod - roll
+    )
+}
+/// In der Wüste regnet es nur selten, auch unter geschlossener Wolkendecke
+fn step4(rng: &mut impl Rng, clouds: &Clouds, wind: &Wind, desert: bool) -> Result<Rain> {
+    let roll = rng.gen_range(1..=20);
+    let does_rain: bool = match clouds {
+        Clouds::NONE => false,
+        Clouds::FEW | Clouds::LOTS if desert => false,
+        Clouds::ALL if desert => roll == 1,
+        Clouds::FEW => roll == 1,
+        Clouds::LOTS => roll <= 4,
+        Clouds::ALL => roll <= 10,
+    };
+
+    if does_rain {
+        let roll = rng.gen_range(1..=20);
+        let rain = match wind {
+            Wind::NONE => match roll {
+                1..=12 => Rain::LITTLE,
+                13..=19 => Rain::LOTS,
+                20 => Rain::ALL,
+                _ => return Err(Error::Roll("Niederschlag", roll))
+            },
+            Wind::LIGHT => match roll {
+                1..=9 => Rain::LITTLE,
+                10..=18 => Rain::LOTS,
+                19..=20 => Rain::ALL,
+                _ => return Err(Error::Roll("Niederschlag", roll))
+            },
+            Wind::SOFT => match roll {
+                1..=7 => Rain::LITTLE,
+                8..=17 => Rain::LOTS,
+                18..=20 => Rain::ALL,
+                _ => return Err(Error::Roll("Niederschlag", roll))
+            },
+            Wind::FRESH => match roll {
+                1..=5 => Rain::LITTLE,
+                6..=16 => Rain::LOTS,
+                17..=20 => Rain::ALL,
+                _ => return Err(Error::Roll("Niederschlag", roll))
+            },
+            Wind::COOL => match roll {
+                1..=3 => Rain::LITTLE,
+                4..=15 => Rain::LOTS,
+                16..=20 => Rain::ALL,
+                _ => return Err(Error::Roll("Niederschlag", roll))
+            },
+            Wind::STRONG => match roll {
+                1..=2 => Rain::LITTLE,
+                3..=13 => Rain::LOTS,
+                14..=20 => Rain::ALL,
+                _ => return Err(Error::Roll("Niederschlag", roll))
+            },
+            Wind::STORM | Wind::SANDSTORM => match roll {
+                1 => Rain::LITTLE,
+                2..=10 => Rain::LOTS,
+                11..=20 => Rain::ALL,
+                _ => return Err(Error::Roll("Niederschlag", roll))
+            },
+        };
+        return Ok(rain);
+    }
+    Ok(Rain::NONE)
+}
+/// Besondere Wetterereignisse. Gewürfelt wird nur, wenn das Wetter des Tages zum Ereignis passt.
+/// Bei Hagel wird aus dem Regen des Tages Hagel.
+fn step5(day: &mut Day, rng: &mut impl Rng) -> Result<()> {
+    for p in Phenomenon::ALL {
+        let happens = match p.chance(day) {
+            0 => false,
+            20.. => true,
+            chance => rng.gen_range(1..=20) <= chance,
+        };
+        if happens {
+            if p == Phenomenon::HAIL {day.precipitation = Some(Precipitation::HAIL);}
+            day.events.push(WeatherEvent::new(p, rng)?);
+        }
+    }
+    Ok(())
+}
+fn step6(params: &WeatherParams, rng: &mut impl Rng) -> Result<Changes> {
+    let roll = rng.gen_range(1..=20);
+    let changes = if [Season::SUMMER,Season::WINTER].contains(&params.season) {
+        match roll {
+            1..=9 => Changes::NONE,
+            10 => Changes::WIND,
+            11 => Changes::TEMPERATURE,
+            12 => Changes::RAIN,
+            13 => Changes::CLOUDS | Changes::RAIN,
+            14 => Changes::WIND | Changes::TEMPERATURE,
+            15 => Changes::WIND | Changes::TEMPERATURE,
+            16 => Changes::RAIN | Changes::TEMPERATURE,
+            17 => Changes::ALL - Changes::CLOUDS,
+            18 => Changes::ALL - Changes::WIND,
+            19 => Changes::ALL - Changes::TEMPERATURE,
+            20 => Changes::ALL,
+            _ => return Err(Error::Roll("Wetterwechsel", roll))
+        }
+    }
+    else {
+        match roll {
+            1..=4 => Changes::NONE,
+            5 => Changes::WIND,
+            6 => Changes::TEMPERATURE,
+            7 => Changes::RAIN,
+            8..=9 => Changes::CLOUDS | Changes::RAIN,
+            10..=11 => Changes::WIND | Changes::TEMPERATURE,
+            12..=13 => Changes::WIND | Changes::TEMPERATURE,
+            14..=15 => Changes::RAIN | Changes::TEMPERATURE,
+            16 => Changes::ALL - Changes::CLOUDS,
+            17 => Changes::ALL - Changes::WIND,
+            18 => Changes::ALL - Changes::TEMPERATURE,
+            19..=20 => Changes::ALL,
+            _ => return Err(Error::Roll("Wetterwechsel", roll))
+        }
+    };
+    Ok(changes)
+}